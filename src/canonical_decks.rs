@@ -6,11 +6,19 @@
 //!   * Provide project-local placeholders for "easy win" and
 //!     "unsolvable but playable" deals, with tests that only check that
 //!     they are valid permutations of a standard deck.
+//!   * Provide a deal that has legal moves but has been shown unwinnable
+//!     by exhaustive DFS (`canonical_verified_unsolvable_deck`).
+//!   * Provide near-win positions (`canonical_near_win_tableau`) for
+//!     end-game heuristics such as auto-complete.
+//!   * Provide deals with every Ace playable at once, or every Ace buried,
+//!     for foundation-move tests.
 //!
 //! This module **does not** depend on search.rs / DFS. It just works with
-//! Card/Suit/Rank and known Klondike accessibility conditions.
+//! Card/Suit/Rank and known Klondike accessibility conditions; only the
+//! tests run the solver, to re-check the verified-unsolvable deal.
 
-use crate::card::{Card, Suit, Rank, CARDS_PER_DECK};
+use crate::card::{Card, Suit, Rank, CARDS_PER_DECK, NUM_RANKS};
+use crate::tableau::Tableau;
 
 /// Local convenience: our deck length as `usize`.
 const DECK_LEN: usize = CARDS_PER_DECK as usize;
@@ -268,11 +276,120 @@ pub fn canonical_easy_win_deck() -> [Card; DECK_LEN] {
 
 /// Project-local "unsolvable but with moves" deck.
 ///
-/// Placeholder: currently just the standard ordered deck; you can later
-/// replace this with a 52-card permutation that is known (from theory
-/// or exhaustive search) to have legal moves but no winning line.
+/// Same deal as `canonical_verified_unsolvable_deck`.
 pub fn canonical_unsolvable_but_playable_deck() -> [Card; DECK_LEN] {
-    standard_deck_suit_rank()
+    canonical_verified_unsolvable_deck()
+}
+
+/// A deal with legal opening moves but no winning line, in dealing order
+/// (deck index 0 first, as `Tableau::deal_from_shuffled` takes it).
+///
+/// This is `card::shuffled_deck_from_seed(244)`, written out so it cannot
+/// drift if that shuffle ever changes. It was verified with
+/// `solve_single_deck_with_config` under draw-3, unlimited redeals,
+/// `max_nodes = 10_000_000` and `max_depth = 4096`: with
+/// `prefer_foundation_first` off the DFS runs out of states after 2,409
+/// nodes (deepest branch 59 moves) without a win and without touching
+/// either limit, so the search was exhaustive. With the foundation-first
+/// shortcut on (1,258 nodes) or the transposition table (7,031 nodes) it
+/// reaches the same verdict.
+pub fn canonical_verified_unsolvable_deck() -> [Card; DECK_LEN] {
+    use Rank::*;
+    use Suit::*;
+
+    const DEAL: [(Suit, Rank); DECK_LEN] = [
+        (Clubs, Ten), (Hearts, Three), (Clubs, Two), (Clubs, Queen),
+        (Diamonds, Ten), (Hearts, King), (Hearts, Seven), (Spades, Ace),
+        (Clubs, Four), (Clubs, Six), (Hearts, Nine), (Diamonds, Six),
+        (Hearts, Jack), (Spades, Seven), (Hearts, Two), (Spades, Five),
+        (Hearts, Queen), (Diamonds, Jack), (Hearts, Ten), (Spades, Three),
+        (Hearts, Ace), (Diamonds, Ace), (Clubs, Eight), (Diamonds, Nine),
+        (Spades, Queen), (Spades, Nine), (Diamonds, Seven), (Diamonds, Three),
+        (Clubs, Nine), (Diamonds, Four), (Spades, Jack), (Spades, Eight),
+        (Spades, King), (Clubs, Jack), (Spades, Four), (Clubs, Seven),
+        (Clubs, Five), (Hearts, Six), (Hearts, Four), (Clubs, Three),
+        (Clubs, Ace), (Spades, Two), (Clubs, King), (Diamonds, King),
+        (Diamonds, Two), (Spades, Ten), (Diamonds, Eight), (Hearts, Eight),
+        (Hearts, Five), (Diamonds, Five), (Diamonds, Queen), (Spades, Six),
+    ];
+
+    DEAL.map(|(suit, rank)| Card::new(suit, rank))
+}
+
+/// A deal where all four Aces are face-up column tops, so the opening
+/// position has exactly four foundation moves, one per Ace.
+///
+/// `Tableau::deal_from_shuffled` turns deck[21..28] face-up onto columns
+/// 7..1 (right to left), so the Aces go at:
+///
+/// ```text
+///   deck[24] = AC -> column 4
+///   deck[25] = AD -> column 3
+///   deck[26] = AH -> column 2
+///   deck[27] = AS -> column 1
+/// ```
+///
+/// Every other card keeps its place from the suit-major ordered deck,
+/// with the four cards that were at deck[24..28] moved to the Aces' old
+/// slots.
+pub fn canonical_all_aces_accessible_deck() -> [Card; DECK_LEN] {
+    let mut deck = standard_deck_suit_rank();
+    for i in 0..4 {
+        deck.swap(i * NUM_RANKS as usize, 24 + i);
+    }
+    deck
+}
+
+/// A deal where all four Aces are face-down at the very bottom of
+/// columns 4..7, so no foundation move is possible until those columns
+/// are cleared down to them.
+///
+/// Round one of the face-down deal places deck[0..6] on columns 7..2
+/// (right to left), so the Aces go at:
+///
+/// ```text
+///   deck[0] = AC -> column 7, bottom card
+///   deck[1] = AD -> column 6, bottom card
+///   deck[2] = AH -> column 5, bottom card
+///   deck[3] = AS -> column 4, bottom card
+/// ```
+pub fn canonical_all_aces_buried_deck() -> [Card; DECK_LEN] {
+    let mut deck = standard_deck_suit_rank();
+    for i in 1..4 {
+        deck.swap(i * NUM_RANKS as usize, i);
+    }
+    deck
+}
+
+/// Near-win position: every card is face-up, stock and waste are empty,
+/// and exactly `moves_to_finish` foundation moves win the game.
+///
+/// No dealt deck can start like this (a fresh deal always has 21
+/// face-down cards), so this returns the position itself rather than a
+/// deck. The remaining cards are taken off the foundations round-robin
+/// over `Suit::ALL`, highest rank first, and each suit's cards are stacked
+/// in its own column (index = suit) with the lowest rank on top. So
+/// `moves_to_finish == 1` leaves 51 cards on the foundations and one King
+/// on a column, and `moves_to_finish == 4` leaves one King of each suit.
+///
+/// Returns `None` unless `1 <= moves_to_finish <= 52`.
+pub fn canonical_near_win_tableau(moves_to_finish: u8) -> Option<Tableau> {
+    if moves_to_finish == 0 || moves_to_finish > CARDS_PER_DECK {
+        return None;
+    }
+
+    let mut tab = Tableau::new_empty();
+    tab.foundations = [NUM_RANKS; 4];
+    for i in 0..moves_to_finish as usize {
+        tab.foundations[i % Suit::ALL.len()] -= 1;
+    }
+
+    for (i, &suit) in Suit::ALL.iter().enumerate() {
+        for rank in (tab.foundations[i]..NUM_RANKS).rev() {
+            tab.columns[i].push(Card::new(suit, Rank::from_u8(rank)), false);
+        }
+    }
+    Some(tab)
 }
 
 #[cfg(test)]
@@ -355,5 +472,115 @@ mod tests {
         check_is_permutation(&unplayable);
         check_is_permutation(&easy);
         check_is_permutation(&hard);
+        check_is_permutation(&canonical_all_aces_accessible_deck());
+        check_is_permutation(&canonical_all_aces_buried_deck());
+    }
+
+    #[test]
+    fn verified_unsolvable_deck_has_moves_but_no_win() {
+        use crate::game::TerminationReason;
+        use crate::moves::generate_legal_moves;
+        use crate::search::{solve_single_deck_with_config, DetailLevel, SearchConfig, SearchLimits};
+
+        let deck = canonical_verified_unsolvable_deck();
+        check_is_permutation(&deck);
+        assert_eq!(deck, crate::card::shuffled_deck_from_seed(244));
+        assert!(!is_unplayable_by_local_conditions(&deck));
+        assert!(!generate_legal_moves(&Tableau::deal_from_shuffled(deck)).is_empty());
+
+        let limits = SearchLimits::builder()
+            .max_nodes(10_000_000)
+            .max_depth(4096)
+            .max_redeals(None)
+            .build();
+        let cfg = SearchConfig::builder()
+            .limits(limits)
+            .detail(DetailLevel::Silent)
+            .prefer_foundation_first(false)
+            .build();
+        let outcome = solve_single_deck_with_config(deck, &cfg);
+
+        println!(
+            "verified unsolvable deck: {} nodes, deepest branch {}, {:?}",
+            outcome.nodes_visited, outcome.max_branch_depth, outcome.termination
+        );
+        assert!(!outcome.is_win);
+        // Only an exhausted search proves anything.
+        assert!(matches!(
+            outcome.termination,
+            TerminationReason::LossNoMoreMoves | TerminationReason::LoopOnLastBranch
+        ));
+        // `termination` only describes the last branch; also check that no
+        // branch hit the depth cap and the node budget was not used up.
+        assert!(outcome.max_branch_depth < limits.max_depth);
+        assert!(outcome.nodes_visited < limits.max_nodes);
+    }
+
+    #[test]
+    fn near_win_tableau_finishes_in_exactly_the_requested_moves() {
+        use crate::moves::MoveKind;
+
+        assert!(canonical_near_win_tableau(0).is_none());
+        assert!(canonical_near_win_tableau(53).is_none());
+
+        for k in [1u8, 2, 4, 5, 13, 52] {
+            let tab = canonical_near_win_tableau(k).expect("count in range");
+            assert_eq!(tab.total_cards(), 52);
+            assert_eq!(tab.foundations.iter().map(|&f| f as u32).sum::<u32>(), 52 - k as u32);
+            assert!(tab.is_auto_completable());
+
+            let tail = tab.auto_complete_moves();
+            assert_eq!(tail.len(), k as usize, "moves_to_finish = {}", k);
+            assert!(tail.iter().all(|mv| matches!(mv.kind, MoveKind::ColumnToFoundation { .. })));
+
+            let mut done = tab;
+            for mv in tail {
+                mv.apply(&mut done);
+            }
+            assert!(done.is_win());
+        }
+
+        // One card left: a single King on its column.
+        let one = canonical_near_win_tableau(1).unwrap();
+        assert_eq!(one.columns[0].top(), Some(Card::new(Suit::Hearts, Rank::King)));
+    }
+
+    #[test]
+    fn all_aces_accessible_deck_opens_with_four_ace_plays() {
+        use crate::moves::{generate_legal_moves, MoveKind};
+
+        let tab = Tableau::deal_from_shuffled(canonical_all_aces_accessible_deck());
+        crate::display::print_tableau(&tab);
+        let aces: Vec<Card> = generate_legal_moves(&tab)
+            .iter()
+            .filter_map(|mv| match mv.kind {
+                MoveKind::ColumnToFoundation { src_col } => tab.columns[src_col as usize].top(),
+                MoveKind::WasteToFoundation => tab.waste.top(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(aces.len(), 4);
+        assert!(aces.iter().all(|c| c.rank() == Rank::Ace));
+        for (col, suit) in [(3, Suit::Clubs), (2, Suit::Diamonds), (1, Suit::Hearts), (0, Suit::Spades)] {
+            assert_eq!(tab.columns[col].top(), Some(Card::new(suit, Rank::Ace)));
+        }
+    }
+
+    #[test]
+    fn all_aces_buried_deck_has_no_foundation_moves() {
+        use crate::moves::{generate_legal_moves, MoveKind};
+
+        let tab = Tableau::deal_from_shuffled(canonical_all_aces_buried_deck());
+        crate::display::print_tableau(&tab);
+        assert!(!generate_legal_moves(&tab).iter().any(|mv| matches!(
+            mv.kind,
+            MoveKind::ColumnToFoundation { .. } | MoveKind::WasteToFoundation
+        )));
+        for col in 3..7 {
+            let bottom = tab.columns[col].iter_all().next().unwrap();
+            assert_eq!(bottom.rank(), Rank::Ace, "column {}", col + 1);
+            assert!(tab.columns[col].num_face_down() > 0);
+        }
+        assert!(!tab.stock.iter().any(|c| c.rank() == Rank::Ace));
     }
 }
@@ -4,6 +4,12 @@
 //! - `Suit` and `Rank` give human-readable structure on top of that.
 
 use core::fmt;
+use core::str::FromStr;
+
+use crate::moves::{can_move_to_foundation, can_place_on_column};
+use crate::tableau::Tableau;
+
+pub use crate::parse::CardParseError;
 
 /// Number of suits in a standard deck.
 pub const NUM_SUITS: u8 = 4;
@@ -19,11 +25,15 @@ pub const CARDS_PER_DECK: u8 = NUM_SUITS * NUM_RANKS;
 /// index = suit as u8 * 13 + rank as u8
 /// ```
 /// where `rank` is 0=Ace, 1=Two, ..., 12=King.
+///
+/// With the `serde` feature, a card serializes as its `ascii_str` (e.g.
+/// `"AH"`) rather than the raw index, so saved files stay readable.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Card(pub u8);
 
 /// The four suits in a standard deck.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Suit {
     Hearts = 0,
@@ -37,6 +47,7 @@ pub enum Suit {
 /// Note: Ace is treated as the lowest rank here (0), and you can use
 /// `rank_number()` on `Card` to get 1..=13 as a convenience.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Rank {
     Ace = 0,
@@ -113,9 +124,23 @@ impl Card {
         }
     }
 
-    /// Short string like "AH", "7C", "TD", "KS".
-    pub fn short_str(self) -> String {
-        let r = match self.rank() {
+    /// True if this card can be played onto its suit's foundation in `tab`
+    /// (an Ace on an empty foundation, otherwise exactly the next rank).
+    #[inline]
+    pub fn can_go_on_foundation(self, tab: &Tableau) -> bool {
+        can_move_to_foundation(tab, self)
+    }
+
+    /// True if this card can be placed onto `below` in a tableau column
+    /// (opposite color, one rank lower).
+    #[inline]
+    pub fn can_go_on_column(self, below: Card) -> bool {
+        can_place_on_column(below, self)
+    }
+
+    /// Rank character: 'A', '2'..'9', 'T', 'J', 'Q', 'K'.
+    fn rank_char(self) -> char {
+        match self.rank() {
             Rank::Ace => 'A',
             Rank::Two => '2',
             Rank::Three => '3',
@@ -129,9 +154,79 @@ impl Card {
             Rank::Jack => 'J',
             Rank::Queen => 'Q',
             Rank::King => 'K',
-        };
+        }
+    }
+
+    /// Short string like "AH", "7C", "TD", "KS".
+    ///
+    /// With the `unicode-suits` feature the suit is a Unicode symbol
+    /// instead ("A♥", "7♣", "T♦", "K♠"). Each symbol is one `char`, so
+    /// width-based padding such as `{:>3}` still lines up.
+    pub fn short_str(self) -> String {
+        #[cfg(feature = "unicode-suits")]
+        let s = self.suit().unicode_char();
+        #[cfg(not(feature = "unicode-suits"))]
         let s = self.suit().short_char();
-        format!("{r}{s}")
+        format!("{}{s}", self.rank_char())
+    }
+
+    /// Short ASCII string like "AH", regardless of the `unicode-suits`
+    /// feature. Machine-readable formats (compact tableau strings, serde)
+    /// use this so their output does not depend on build features.
+    pub fn ascii_str(self) -> String {
+        format!("{}{}", self.rank_char(), self.suit().short_char())
+    }
+
+    /// Parse the `short_str` form ("AH", "7C", "TD", "KS"; case-insensitive).
+    /// Unicode suit symbols ("A♥") are accepted too.
+    ///
+    /// Returns `None` if the string is not exactly a rank character
+    /// followed by a suit character; use `str::parse` (`FromStr`) to learn
+    /// which part was wrong.
+    pub fn from_short_str(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.ascii_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Card::from_short_str(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid card '{}'", s)))
+    }
+}
+
+/// Parses the `short_str` form; see `Card::from_short_str`.
+impl FromStr for Card {
+    type Err = CardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(r), Some(su), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(CardParseError::InvalidLength);
+        };
+        let rank = match r.to_ascii_uppercase() {
+            'A' => Rank::Ace,
+            '2'..='9' => Rank::from_u8(r as u8 - b'1'),
+            'T' => Rank::Ten,
+            'J' => Rank::Jack,
+            'Q' => Rank::Queen,
+            'K' => Rank::King,
+            _ => return Err(CardParseError::UnknownRank(r)),
+        };
+        let suit = Suit::ALL
+            .into_iter()
+            .find(|st| st.short_char() == su.to_ascii_uppercase() || st.unicode_char() == su)
+            .ok_or(CardParseError::UnknownSuit(su))?;
+        Ok(Card::new(suit, rank))
     }
 }
 
@@ -176,6 +271,18 @@ impl Suit {
             Suit::Diamonds => 'D',
         }
     }
+
+    /// Unicode suit symbol: '♥', '♣', '♠', or '♦' (U+2665, U+2663,
+    /// U+2660, U+2666).
+    #[inline]
+    pub fn unicode_char(self) -> char {
+        match self {
+            Suit::Hearts => '\u{2665}',
+            Suit::Clubs => '\u{2663}',
+            Suit::Spades => '\u{2660}',
+            Suit::Diamonds => '\u{2666}',
+        }
+    }
 }
 
 impl Rank {
@@ -226,6 +333,32 @@ impl Rank {
     pub fn number(self) -> u8 {
         self as u8 + 1
     }
+
+    /// The next rank up, or `None` for King.
+    #[inline]
+    pub fn succ(self) -> Option<Rank> {
+        let next = (self as u8).checked_add(1)?;
+        (next < NUM_RANKS).then(|| Rank::from_u8(next))
+    }
+
+    /// The next rank down, or `None` for Ace.
+    #[inline]
+    pub fn pred(self) -> Option<Rank> {
+        (self as u8).checked_sub(1).map(Rank::from_u8)
+    }
+
+    /// Signed rank difference `other - self`: positive when `other` is the
+    /// higher rank (`Ace.distance_to(Three) == 2`).
+    #[inline]
+    pub fn distance_to(self, other: Rank) -> i8 {
+        other as i8 - self as i8
+    }
+
+    /// True if `self` is exactly one rank below `other` (Ten below Jack).
+    #[inline]
+    pub fn is_one_below(self, other: Rank) -> bool {
+        self.distance_to(other) == 1
+    }
 }
 
 /// Helper for tableau rules: can `upper` be placed on `lower`?
@@ -235,7 +368,7 @@ impl Rank {
 /// - `upper` is opposite color from `lower`.
 #[inline]
 pub fn is_one_lower_opposite_color(upper: Card, lower: Card) -> bool {
-    upper.rank_number() + 1 == lower.rank_number()
+    upper.rank().is_one_below(lower.rank())
         && upper.color() != lower.color()
 }
 
@@ -279,6 +412,40 @@ pub fn shuffled_deck_from_seed(seed: u32) -> [Card; CARDS_PER_DECK as usize] {
     deck
 }
 
+/// Return a deterministically shuffled standard deck given a 64-bit seed.
+///
+/// A separate generator from `shuffled_deck_from_seed`, so the two do not
+/// give the same deck for the same seed value. The seed is scrambled with
+/// SplitMix64's finalizer (so nearby seeds start far apart), then a 64-bit
+/// LCG (Knuth's MMIX constants) drives a Fisher–Yates shuffle. Each swap
+/// index comes from the LCG's high 32 bits, scaled into `0..=i` by a
+/// multiply-shift rather than `%`, since the low bits of a power-of-two
+/// LCG are weak.
+pub fn shuffled_deck_from_seed_u64(seed: u64) -> [Card; CARDS_PER_DECK as usize] {
+    let mut deck = standard_deck();
+
+    let mut state = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    state = (state ^ (state >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    state = (state ^ (state >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    state ^= state >> 31;
+
+    fn lcg(state: &mut u64) -> u64 {
+        *state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *state
+    }
+
+    let len = deck.len();
+    for i in (1..len).rev() {
+        let high = lcg(&mut state) >> 32;
+        let r = ((high * (i as u64 + 1)) >> 32) as usize;
+        deck.swap(i, r);
+    }
+
+    deck
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -339,6 +506,7 @@ mod tests {
         }
     }
 
+    #[cfg(not(feature = "unicode-suits"))]
     #[test]
     fn short_str_and_display() {
         let ah = Card::new(Suit::Hearts, Rank::Ace);
@@ -357,6 +525,55 @@ mod tests {
         assert_eq!(format!("{seven_clubs}"), "7C");
     }
 
+    #[cfg(feature = "unicode-suits")]
+    #[test]
+    fn short_str_uses_unicode_suits() {
+        let ah = Card::new(Suit::Hearts, Rank::Ace);
+        let td = Card::new(Suit::Diamonds, Rank::Ten);
+
+        assert_eq!(ah.short_str(), "A♥");
+        assert_eq!(format!("{td}"), "T♦");
+        assert_eq!(ah.short_str().chars().count(), 2);
+    }
+
+    #[test]
+    fn ascii_str_is_always_ascii() {
+        assert_eq!(Card::new(Suit::Hearts, Rank::Ace).ascii_str(), "AH");
+        assert_eq!(Card::new(Suit::Spades, Rank::King).ascii_str(), "KS");
+        for card in standard_deck() {
+            assert!(card.ascii_str().is_ascii());
+        }
+    }
+
+    #[test]
+    fn from_short_str_round_trip() {
+        for card in standard_deck() {
+            assert_eq!(Card::from_short_str(&card.short_str()), Some(card));
+            assert_eq!(Card::from_short_str(&card.ascii_str()), Some(card));
+        }
+        assert_eq!(
+            Card::from_short_str("A♥"),
+            Some(Card::new(Suit::Hearts, Rank::Ace))
+        );
+        assert_eq!(
+            Card::from_short_str("qd"),
+            Some(Card::new(Suit::Diamonds, Rank::Queen))
+        );
+        for bad in ["", "A", "1H", "AX", "10H", "AHX"] {
+            assert_eq!(Card::from_short_str(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn card_serializes_as_short_str() {
+        let td = Card::new(Suit::Diamonds, Rank::Ten);
+        let json = serde_json::to_string(&td).unwrap();
+        assert_eq!(json, "\"TD\"");
+        assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), td);
+        assert!(serde_json::from_str::<Card>("\"ZZ\"").is_err());
+    }
+
     #[test]
     fn standard_deck_has_52_unique_cards() {
         let deck = standard_deck();
@@ -382,4 +599,127 @@ mod tests {
         assert!(is_one_lower_opposite_color(seven_spades, eight_hearts));
         assert!(!is_one_lower_opposite_color(seven_hearts, eight_hearts));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn can_go_on_foundation_needs_ace_then_next_rank() {
+        let mut tab = Tableau::new_empty();
+        for (f, &suit) in Suit::ALL.iter().enumerate() {
+            // Empty foundation: only the Ace.
+            assert!(Card::new(suit, Rank::Ace).can_go_on_foundation(&tab));
+            assert!(!Card::new(suit, Rank::Two).can_go_on_foundation(&tab));
+            assert!(!Card::new(suit, Rank::King).can_go_on_foundation(&tab));
+
+            // Foundation up to Five: only the Six.
+            tab.foundations[f] = 5;
+            assert!(Card::new(suit, Rank::Six).can_go_on_foundation(&tab));
+            assert!(!Card::new(suit, Rank::Five).can_go_on_foundation(&tab));
+            assert!(!Card::new(suit, Rank::Seven).can_go_on_foundation(&tab));
+            assert!(!Card::new(suit, Rank::Ace).can_go_on_foundation(&tab));
+
+            // Another suit's foundation does not help.
+            let other = Suit::ALL[(f + 1) % Suit::ALL.len()];
+            assert!(!Card::new(other, Rank::Six).can_go_on_foundation(&tab));
+            tab.foundations[f] = 0;
+        }
+    }
+
+    #[test]
+    fn can_go_on_column_needs_opposite_color_one_rank_lower() {
+        let eight_spades = Card::new(Suit::Spades, Rank::Eight);
+        let eight_hearts = Card::new(Suit::Hearts, Rank::Eight);
+
+        // Red on black.
+        assert!(Card::new(Suit::Hearts, Rank::Seven).can_go_on_column(eight_spades));
+        assert!(Card::new(Suit::Diamonds, Rank::Seven).can_go_on_column(eight_spades));
+        // Black on red.
+        assert!(Card::new(Suit::Clubs, Rank::Seven).can_go_on_column(eight_hearts));
+        assert!(Card::new(Suit::Spades, Rank::Seven).can_go_on_column(eight_hearts));
+
+        // Same color, or wrong rank.
+        assert!(!Card::new(Suit::Clubs, Rank::Seven).can_go_on_column(eight_spades));
+        assert!(!Card::new(Suit::Diamonds, Rank::Seven).can_go_on_column(eight_hearts));
+        assert!(!Card::new(Suit::Hearts, Rank::Six).can_go_on_column(eight_spades));
+        assert!(!Card::new(Suit::Hearts, Rank::Nine).can_go_on_column(eight_spades));
+    }
+
+    #[test]
+    fn rank_succ_and_pred_stop_at_king_and_ace() {
+        assert_eq!(Rank::King.succ(), None);
+        assert_eq!(Rank::Ace.pred(), None);
+        assert_eq!(Rank::Seven.succ(), Some(Rank::Eight));
+        assert_eq!(Rank::Seven.pred(), Some(Rank::Six));
+        assert_eq!(Rank::Ace.succ(), Some(Rank::Two));
+        assert_eq!(Rank::King.pred(), Some(Rank::Queen));
+
+        for pair in Rank::ALL.windows(2) {
+            assert_eq!(pair[0].succ(), Some(pair[1]));
+            assert_eq!(pair[1].pred(), Some(pair[0]));
+        }
+        for &rank in Rank::ALL.iter() {
+            if let Some(up) = rank.succ() {
+                assert_eq!(up.pred(), Some(rank));
+            }
+        }
+    }
+
+    #[test]
+    fn from_str_round_trips_every_card() {
+        for card in standard_deck() {
+            assert_eq!(Card::from_str(&card.short_str()), Ok(card));
+            assert_eq!(Card::from_str(&card.ascii_str()), Ok(card));
+            assert_eq!(card.ascii_str().to_lowercase().parse::<Card>(), Ok(card));
+        }
+        assert_eq!("ah".parse(), Ok(Card::new(Suit::Hearts, Rank::Ace)));
+    }
+
+    #[test]
+    fn from_str_reports_what_was_wrong() {
+        assert_eq!(Card::from_str(""), Err(CardParseError::InvalidLength));
+        assert_eq!(Card::from_str("A"), Err(CardParseError::InvalidLength));
+        assert_eq!(Card::from_str("10H"), Err(CardParseError::InvalidLength));
+        assert_eq!(Card::from_str("1H"), Err(CardParseError::UnknownRank('1')));
+        assert_eq!(Card::from_str("XH"), Err(CardParseError::UnknownRank('X')));
+        assert_eq!(Card::from_str("Ax"), Err(CardParseError::UnknownSuit('x')));
+        assert_eq!(Card::from_short_str("Ax"), None);
+    }
+
+    #[test]
+    fn shuffled_deck_from_seed_u64_is_a_distinct_deterministic_permutation() {
+        let mut differs = 0;
+        for seed in 0..100u32 {
+            let deck = shuffled_deck_from_seed_u64(seed as u64);
+            assert_eq!(deck, shuffled_deck_from_seed_u64(seed as u64));
+
+            let mut seen = [false; CARDS_PER_DECK as usize];
+            for c in deck {
+                assert!(!seen[c.index() as usize], "seed {}: duplicate {}", seed, c.short_str());
+                seen[c.index() as usize] = true;
+            }
+            assert!(seen.iter().all(|&s| s));
+
+            if deck != shuffled_deck_from_seed(seed) {
+                differs += 1;
+            }
+        }
+        assert!(differs > 0, "the u64 shuffle must not just repeat the u32 one");
+
+        // Seeds beyond the u32 range still give different decks.
+        assert_ne!(
+            shuffled_deck_from_seed_u64(u32::MAX as u64 + 1),
+            shuffled_deck_from_seed_u64(u32::MAX as u64 + 2)
+        );
+    }
+
+    #[test]
+    fn rank_distance_and_one_below() {
+        assert_eq!(Rank::Ace.distance_to(Rank::Three), 2);
+        assert_eq!(Rank::King.distance_to(Rank::Ace), -12);
+        assert_eq!(Rank::Seven.distance_to(Rank::Seven), 0);
+        assert!(Rank::Ten.is_one_below(Rank::Jack));
+        assert!(!Rank::Jack.is_one_below(Rank::Ten));
+        assert!(!Rank::Queen.is_one_below(Rank::Queen));
+        for r in Rank::ALL {
+            assert_eq!(r.succ().is_some_and(|next| r.is_one_below(next)), r != Rank::King);
+        }
+    }
+}
@@ -6,20 +6,128 @@
 //!
 //! The intent is to give a stable, readable CLI representation that is
 //! useful for debugging and for logging winning lines of play.
+//!
+//! Output can optionally be colored with ANSI escape codes (red suits in
+//! red, face-down cards in gray). Coloring is controlled by a process-wide
+//! `ColorMode` (see `set_color_mode`), which the plain render functions
+//! consult; the `*_with_mode` variants take the mode explicitly. With
+//! `ColorMode::NoColor` (the default) output is plain text.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use crate::card::{Card, Rank, Suit, CARDS_PER_DECK};
+use crate::game::GameState;
+use crate::moves::Move;
+use crate::parse::MoveParseError;
+use crate::tableau::{DrawRule, Pile, Tableau, NUM_COLS};
+
+/// Whether rendered cards carry ANSI color codes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Red suits in red, face-down cards in gray.
+    Color,
+    /// Plain text only.
+    #[default]
+    NoColor,
+}
+
+/// Process-wide color switch used by the plain render functions.
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Set the color mode used by `render_tableau`, `print_tableau`, etc.
+pub fn set_color_mode(mode: ColorMode) {
+    COLOR_ENABLED.store(mode == ColorMode::Color, Ordering::Relaxed);
+}
+
+/// Current process-wide color mode.
+pub fn color_mode() -> ColorMode {
+    if COLOR_ENABLED.load(Ordering::Relaxed) {
+        ColorMode::Color
+    } else {
+        ColorMode::NoColor
+    }
+}
+
+/// Process-wide switch for row numbers in `render_tableau`'s column grid.
+static SHOW_ROW_INDICES: AtomicBool = AtomicBool::new(false);
+
+/// The process-wide display settings that `render_tableau`,
+/// `print_tableau`, etc. consult, gathered in one place.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DisplayConfig {
+    /// Number the rows of the column grid (`render_columns_with_indices`).
+    pub show_row_indices: bool,
+    /// Same setting as `set_color_mode` / `color_mode`.
+    pub color_mode: ColorMode,
+}
+
+/// Replace the process-wide display settings.
+pub fn set_display_config(cfg: DisplayConfig) {
+    SHOW_ROW_INDICES.store(cfg.show_row_indices, Ordering::Relaxed);
+    set_color_mode(cfg.color_mode);
+}
+
+/// Current process-wide display settings.
+pub fn display_config() -> DisplayConfig {
+    DisplayConfig {
+        show_row_indices: SHOW_ROW_INDICES.load(Ordering::Relaxed),
+        color_mode: color_mode(),
+    }
+}
+
+/// Process-wide number of waste cards `render_tableau` shows.
+static WASTE_PEEK: AtomicU8 = AtomicU8::new(3);
+
+/// Set how many waste cards `render_tableau`, `print_tableau`, etc. show
+/// (see `render_stock_peek`). The default is 3, the cards a draw-3 deal
+/// leaves visible.
+pub fn set_waste_peek(n: u8) {
+    WASTE_PEEK.store(n, Ordering::Relaxed);
+}
+
+/// Current process-wide waste peek depth.
+pub fn waste_peek() -> u8 {
+    WASTE_PEEK.load(Ordering::Relaxed)
+}
+
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GRAY: &str = "\x1b[90m";
+const ANSI_RESET: &str = "\x1b[0m";
 
-use crate::card::{Card, Rank, Suit};
-use crate::tableau::{Tableau, NUM_COLS};
+/// Wrap already-formatted (and already padded) text for one card in the
+/// color for that card. Black face-up cards keep the terminal default.
+fn paint(text: &str, card: Card, face_up: bool, mode: ColorMode) -> String {
+    if mode == ColorMode::NoColor {
+        return text.to_string();
+    }
+    if !face_up {
+        format!("{ANSI_GRAY}{text}{ANSI_RESET}")
+    } else if card.color() == 'R' {
+        format!("{ANSI_RED}{text}{ANSI_RESET}")
+    } else {
+        text.to_string()
+    }
+}
 
 /// Format a single card for display, either face-up or face-down.
 ///
 /// - Face-down cards are rendered as `"XX"`.
 /// - Face-up cards use `Card::short_str()` such as `"AH"`, `"7C"`, `"TD"`.
+///
+/// Uses the process-wide `color_mode()`.
 pub fn format_card_visible(card: Card, face_up: bool) -> String {
-    if face_up {
+    format_card_visible_with_mode(card, face_up, color_mode())
+}
+
+/// `format_card_visible` with an explicit color mode.
+pub fn format_card_visible_with_mode(card: Card, face_up: bool, mode: ColorMode) -> String {
+    let text = if face_up {
         card.short_str()
     } else {
         "XX".to_string()
-    }
+    };
+    paint(&text, card, face_up, mode)
 }
 
 /// Render only the foundation row.
@@ -34,6 +142,11 @@ pub fn format_card_visible(card: Card, face_up: bool) -> String {
 /// Even if there are multiple cards in a foundation pile, only the *top*
 /// card is shown here, matching typical Klondike presentations.
 pub fn render_foundations(tab: &Tableau) -> String {
+    render_foundations_with_mode(tab, color_mode())
+}
+
+/// `render_foundations` with an explicit color mode.
+pub fn render_foundations_with_mode(tab: &Tableau, mode: ColorMode) -> String {
     let mut s = String::new();
     s.push_str("Foundations: ");
     for (i, &rank_num) in tab.foundations.iter().enumerate() {
@@ -45,28 +158,48 @@ pub fn render_foundations(tab: &Tableau) -> String {
             let rank = Rank::from_u8(rank_num - 1);
             let card = Card::new(suit, rank);
             s.push('[');
-            s.push_str(&card.short_str());
+            s.push_str(&format_card_visible_with_mode(card, true, mode));
             s.push_str("] ");
         }
     }
     s.trim_end().to_string()
 }
 
+/// Render every card on each foundation, Ace up to the top card, in
+/// `Suit::ALL` order:
+///
+/// ```text
+/// Hearts: [AH 2H 3H] Clubs: [AC 2C] Spades: [] Diamonds: [AD]
+/// ```
+///
+/// Cards use the plain ASCII form (`Card::ascii_str`) and no colors, since
+/// this is meant for debug output.
+pub fn render_foundations_verbose(tab: &Tableau) -> String {
+    let piles: Vec<String> = tab
+        .foundations
+        .iter()
+        .zip(Suit::ALL)
+        .map(|(&count, suit)| {
+            let cards: Vec<String> = (0..count)
+                .map(|rank| Card::new(suit, Rank::from_u8(rank)).ascii_str())
+                .collect();
+            format!("{:?}: [{}]", suit, cards.join(" "))
+        })
+        .collect();
+    piles.join(" ")
+}
+
 /// Render the stock (face-down) and waste (face-up) piles on a single line.
 ///
 /// Stock is shown as a count of remaining face-down cards.
 /// Waste shows the top card if present and the number of cards in the waste.
 pub fn render_stock_and_waste(tab: &Tableau) -> String {
-    let mut s = String::new();
-
-    // Stock: we don't reveal internal order, only count.
-    let stock_len = tab.stock.len();
-    if stock_len == 0 {
-        s.push_str("Stock: [empty]");
-    } else {
-        s.push_str(&format!("Stock: [{} cards]", stock_len));
-    }
+    render_stock_and_waste_with_mode(tab, color_mode())
+}
 
+/// `render_stock_and_waste` with an explicit color mode.
+pub fn render_stock_and_waste_with_mode(tab: &Tableau, mode: ColorMode) -> String {
+    let mut s = render_stock_count(tab);
     s.push_str("    "); // spacing
 
     // Waste: show top card if any.
@@ -77,7 +210,7 @@ pub fn render_stock_and_waste(tab: &Tableau) -> String {
         let top = tab.waste.top().expect("waste_len > 0 but no top card");
         s.push_str(&format!(
             "Waste: [{}] ({} cards)",
-            top.short_str(),
+            format_card_visible_with_mode(top, true, mode),
             waste_len
         ));
     }
@@ -85,6 +218,50 @@ pub fn render_stock_and_waste(tab: &Tableau) -> String {
     s
 }
 
+/// Stock shown as a count only: we don't reveal its internal order.
+fn render_stock_count(tab: &Tableau) -> String {
+    let stock_len = tab.stock.len();
+    if stock_len == 0 {
+        "Stock: [empty]".to_string()
+    } else {
+        format!("Stock: [{} cards]", stock_len)
+    }
+}
+
+/// Like `render_stock_and_waste`, but shows the top `min(n, waste.len())`
+/// waste cards, most recently dealt first, with the playable top card
+/// marked by `>`: `Stock: [9 cards]    Waste: >AH 3C 7D (15 cards)`.
+///
+/// Under draw-3 the three cards of the last deal are all visible, so
+/// `n == 3` matches the physical game. With `n == 0` only the count is
+/// shown (`Waste: (15 cards)`); an empty waste is `Waste: [empty]`.
+pub fn render_stock_peek(tab: &Tableau, n: u8) -> String {
+    render_stock_peek_with_mode(tab, n, color_mode())
+}
+
+/// `render_stock_peek` with an explicit color mode.
+pub fn render_stock_peek_with_mode(tab: &Tableau, n: u8, mode: ColorMode) -> String {
+    let mut s = render_stock_count(tab);
+    s.push_str("    "); // spacing
+
+    let waste_len = tab.waste.len();
+    if waste_len == 0 {
+        s.push_str("Waste: [empty]");
+        return s;
+    }
+
+    s.push_str("Waste: ");
+    for (i, &card) in tab.waste.iter_rev().take(n as usize).enumerate() {
+        if i == 0 {
+            s.push('>');
+        }
+        s.push_str(&format_card_visible_with_mode(card, true, mode));
+        s.push(' ');
+    }
+    s.push_str(&format!("({} cards)", waste_len));
+    s
+}
+
 /// Render all tableau columns as a multi-line string.
 ///
 /// Columns are arranged in 7 vertical stacks. Each "cell" is three characters
@@ -95,6 +272,23 @@ pub fn render_stock_and_waste(tab: &Tableau) -> String {
 /// each column, the lowest non-empty row is the playable edge (the card you
 /// would pick up in a physical game).
 pub fn render_columns(tab: &Tableau) -> String {
+    render_columns_with_mode(tab, color_mode())
+}
+
+/// `render_columns` with an explicit color mode.
+pub fn render_columns_with_mode(tab: &Tableau, mode: ColorMode) -> String {
+    render_column_grid(tab, mode, false)
+}
+
+/// Like `render_columns`, but each row of the grid starts with its 0-based
+/// row number (`   0: `), which is also the card's index within its column.
+pub fn render_columns_with_indices(tab: &Tableau) -> String {
+    render_column_grid(tab, color_mode(), true)
+}
+
+/// Shared body of `render_columns_with_mode` and
+/// `render_columns_with_indices`.
+fn render_column_grid(tab: &Tableau, mode: ColorMode, row_indices: bool) -> String {
     let mut s = String::new();
 
     s.push_str("Columns:\n");
@@ -124,7 +318,12 @@ pub fn render_columns(tab: &Tableau) -> String {
     //   - For rows >= h, print blanks (the column does not extend that far).
     //   - For rows < h, map row directly to internal index 0..h-1 (top..bottom).
     for row in 0..max_height {
-        s.push_str("      "); // left padding under the header label
+        if row_indices {
+            // Same width as the plain padding, so the header still lines up.
+            s.push_str(&format!("{:>4}: ", row));
+        } else {
+            s.push_str("      "); // left padding under the header label
+        }
 
         for col in &tab.columns {
             let h = col.len as usize;
@@ -136,8 +335,11 @@ pub fn render_columns(tab: &Tableau) -> String {
                 let idx = row; // 0..h-1 (top..bottom)
                 let card = col.cards[idx];
                 let face_down = (idx as u8) < col.num_face_down;
-                let rep = format_card_visible(card, !face_down);
-                s.push_str(&format!("{:>3} ", rep));
+                // Pad the plain text first so escape codes do not count
+                // towards the column width.
+                let rep = format_card_visible_with_mode(card, !face_down, ColorMode::NoColor);
+                s.push_str(&paint(&format!("{:>3}", rep), card, !face_down, mode));
+                s.push(' ');
             }
         }
 
@@ -150,21 +352,292 @@ pub fn render_columns(tab: &Tableau) -> String {
 /// Render a full tableau (foundations, stock/waste, and columns) as a
 /// multi-line string.
 pub fn render_tableau(tab: &Tableau) -> String {
+    render_tableau_with_mode(tab, color_mode())
+}
+
+/// `render_tableau` with an explicit color mode.
+pub fn render_tableau_with_mode(tab: &Tableau, mode: ColorMode) -> String {
     let mut s = String::new();
 
-    s.push_str(&render_foundations(tab));
+    s.push_str(&render_foundations_with_mode(tab, mode));
     s.push('\n');
-    s.push_str(&render_stock_and_waste(tab));
+    s.push_str(&render_stock_peek_with_mode(tab, waste_peek(), mode));
     s.push('\n');
     s.push('\n');
-    s.push_str(&render_columns(tab));
+    s.push_str(&render_column_grid(tab, mode, display_config().show_row_indices));
+
+    s
+}
+
+/// One-line summary of a tableau for logs, e.g.
+///
+/// ```text
+/// F:{2,0,5,3} S:18 W:3>KH C:[1>AH,2>9C,0,4>TS,5>2D,6>QC,7>3H]
+/// ```
+///
+/// `F` is the foundation count per suit (`Suit::ALL` order), `S` the stock
+/// size, `W` the waste size and `>` its top card, and `C` each column's
+/// length and `>` its face-up top card. An empty waste or column is just
+/// `0`; a column whose top card is face-down shows `>??`. Cards use
+/// `Card::ascii_str`, and there are no spaces inside a field, so the line
+/// splits on spaces. Use `Tableau::to_compact_str` when the whole tableau
+/// has to be rebuilt.
+pub fn render_tableau_compact(tab: &Tableau) -> String {
+    let foundations: Vec<String> = tab.foundations.iter().map(|n| n.to_string()).collect();
+    let waste = match tab.waste.top() {
+        Some(top) => format!("{}>{}", tab.waste.len(), top.ascii_str()),
+        None => "0".to_string(),
+    };
+    let columns: Vec<String> = (0..NUM_COLS)
+        .map(|c| match (tab.columns[c].len, tab.column_top_face_up_card(c)) {
+            (0, _) => "0".to_string(),
+            (len, Some(top)) => format!("{}>{}", len, top.ascii_str()),
+            (len, None) => format!("{}>??", len),
+        })
+        .collect();
+    format!(
+        "F:{{{}}} S:{} W:{} C:[{}]",
+        foundations.join(","),
+        tab.stock.len(),
+        waste,
+        columns.join(",")
+    )
+}
+
+/// Render a tableau as an HTML5 `<table>` fragment (no `<html>`/`<body>`),
+/// for pasting into Markdown or HTML docs.
+///
+/// The header row holds the stock (card count), the waste top card and the
+/// four foundations; below it is one table column per tableau column, top
+/// card first. Card cells carry CSS classes `card red`, `card black` or
+/// `card hidden` (face-down, shown as `XX`), and use the ASCII card names
+/// (`AH`, `TD`) whatever the `unicode-suits` feature says. Styling is left
+/// to the page.
+pub fn render_tableau_html(tab: &Tableau) -> String {
+    let card_cell = |card: Card, face_up: bool| {
+        if !face_up {
+            "<td class=\"card hidden\">XX</td>".to_string()
+        } else {
+            let color = if card.color() == 'R' { "red" } else { "black" };
+            format!(
+                "<td class=\"card {}\">{}</td>",
+                color,
+                escape_html(&card.ascii_str())
+            )
+        }
+    };
+
+    let mut s = String::new();
+    s.push_str("<table class=\"klondike\">\n<thead>\n<tr>");
+    s.push_str(&format!(
+        "<td class=\"stock\" title=\"stock\">{}</td>",
+        escape_html(&format!("{} cards", tab.stock.len()))
+    ));
+    match tab.waste.top() {
+        Some(top) => s.push_str(&card_cell(top, true)),
+        None => s.push_str("<td class=\"waste empty\"></td>"),
+    }
+    s.push_str("<td></td>");
+    for (i, &rank_num) in tab.foundations.iter().enumerate() {
+        if rank_num == 0 {
+            s.push_str("<td class=\"foundation empty\"></td>");
+        } else {
+            s.push_str(&card_cell(Card::new(Suit::ALL[i], Rank::from_u8(rank_num - 1)), true));
+        }
+    }
+    s.push_str("</tr>\n<tr>");
+    for col_idx in 0..NUM_COLS {
+        s.push_str(&format!("<th>C{}</th>", col_idx + 1));
+    }
+    s.push_str("</tr>\n</thead>\n<tbody>\n");
 
+    let max_height = tab.columns.iter().map(|c| c.len as usize).max().unwrap_or(0);
+    for row in 0..max_height {
+        s.push_str("<tr>");
+        for col in &tab.columns {
+            if row < col.len as usize {
+                s.push_str(&card_cell(col.cards[row], row as u8 >= col.num_face_down));
+            } else {
+                s.push_str("<td></td>");
+            }
+        }
+        s.push_str("</tr>\n");
+    }
+    s.push_str("</tbody>\n</table>\n");
     s
 }
 
-/// Print a tableau to stdout using `render_tableau`.
+/// Escape the characters that are special in HTML text and attributes.
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render what changed between two tableaus, pile by pile.
+///
+/// Piles only ever change at their top end, so each pile is compared card
+/// by card (face-down state included) from the bottom up. For every pile
+/// that differs there is a `~` line naming it with its size before and
+/// after, then a `-` line with the cards no longer there and/or a `+` line
+/// with the new ones, e.g.:
+///
+/// ```text
+/// ~ Stock: 24 -> 21 cards
+/// - Stock: 5S 4S 3S
+/// ~ Waste: 0 -> 3 cards
+/// + Waste: 3S 4S 5S
+/// ```
+///
+/// Cards are listed bottom to top; a face-down column card shows as `XX`,
+/// so turning one over reads as `- XX` / `+ <card>`. Piles are visited in
+/// the order columns C1..C7, stock, waste, then foundations in `Suit::ALL`
+/// order. The diff is purely structural (it does not know which move was
+/// played), and identical tableaus give an empty string.
+pub fn render_tableau_diff(before: &Tableau, after: &Tableau) -> String {
+    let mut out = String::new();
+
+    let column_cards = |tab: &Tableau, i: usize| -> Vec<(Card, bool)> {
+        let col = &tab.columns[i];
+        col.iter_all()
+            .enumerate()
+            .map(|(k, &c)| (c, k < col.num_face_down as usize))
+            .collect()
+    };
+    for i in 0..NUM_COLS {
+        let name = format!("C{}", i + 1);
+        diff_pile(&mut out, &name, &column_cards(before, i), &column_cards(after, i));
+    }
+
+    fn pile_cards<const N: usize>(pile: &Pile<N>) -> Vec<(Card, bool)> {
+        pile.iter().map(|&c| (c, false)).collect()
+    }
+    diff_pile(&mut out, "Stock", &pile_cards(&before.stock), &pile_cards(&after.stock));
+    diff_pile(&mut out, "Waste", &pile_cards(&before.waste), &pile_cards(&after.waste));
+
+    for (i, suit) in Suit::ALL.iter().enumerate() {
+        let foundation_cards = |tab: &Tableau| -> Vec<(Card, bool)> {
+            (0..tab.foundations[i])
+                .map(|r| (Card::new(*suit, Rank::from_u8(r)), false))
+                .collect()
+        };
+        let name = format!("F{}", suit.short_char());
+        diff_pile(&mut out, &name, &foundation_cards(before), &foundation_cards(after));
+    }
+
+    out
+}
+
+/// Append the `~` / `-` / `+` lines of `render_tableau_diff` for one pile
+/// (cards bottom to top, paired with their face-down flag).
+fn diff_pile(out: &mut String, name: &str, before: &[(Card, bool)], after: &[(Card, bool)]) {
+    let common = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    if common == before.len() && common == after.len() {
+        return;
+    }
+    let list = |cards: &[(Card, bool)]| -> String {
+        cards
+            .iter()
+            .map(|&(c, face_down)| if face_down { "XX".to_string() } else { c.short_str() })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    out.push_str(&format!("~ {}: {} -> {} cards\n", name, before.len(), after.len()));
+    if common < before.len() {
+        out.push_str(&format!("- {}: {}\n", name, list(&before[common..])));
+    }
+    if common < after.len() {
+        out.push_str(&format!("+ {}: {}\n", name, list(&after[common..])));
+    }
+}
+
+/// Print a tableau to stdout using `render_tableau` (so with row numbers
+/// when `DisplayConfig::show_row_indices` is set).
 pub fn print_tableau(tab: &Tableau) {
-    println!("{}", render_tableau(tab));
+    write_tableau(tab, &mut io::stdout()).expect("failed to write to stdout");
+}
+
+/// `print_tableau`, writing to `w` instead of stdout: `render_tableau`
+/// followed by a newline.
+pub fn write_tableau(tab: &Tableau, w: &mut impl Write) -> io::Result<()> {
+    writeln!(w, "{}", render_tableau(tab))
+}
+
+/// Render a move list as numbered lines, one per move:
+///
+/// ```text
+///     1: Deal from Stock (drew 3 cards)
+///     2: Waste: AH -> Foundation(Hearts)
+/// ```
+///
+/// The moves are replayed from the deal of `initial_deck` under
+/// `draw_rule`, so each `Move::describe` sees the tableau the move is
+/// played on. Every line, including the last, ends with a newline.
+pub fn render_move_sequence(
+    initial_deck: [Card; CARDS_PER_DECK as usize],
+    moves: &[Move],
+    draw_rule: DrawRule,
+) -> String {
+    render_move_sequence_impl(initial_deck, moves, draw_rule, false)
+}
+
+/// `render_move_sequence`, with the tableau each move is played on
+/// rendered (via `render_tableau`) above that move's line.
+pub fn render_move_sequence_verbose(
+    initial_deck: [Card; CARDS_PER_DECK as usize],
+    moves: &[Move],
+    draw_rule: DrawRule,
+) -> String {
+    render_move_sequence_impl(initial_deck, moves, draw_rule, true)
+}
+
+fn render_move_sequence_impl(
+    initial_deck: [Card; CARDS_PER_DECK as usize],
+    moves: &[Move],
+    draw_rule: DrawRule,
+    with_tableau: bool,
+) -> String {
+    let mut s = String::new();
+    let mut replay = GameState::new(initial_deck, draw_rule);
+    for (i, mv) in moves.iter().enumerate() {
+        let tab = replay.current_tableau();
+        if with_tableau {
+            s.push_str(&render_tableau(&tab));
+            s.push('\n');
+        }
+        s.push_str(&format!("  {:3}: {}\n", i + 1, mv.describe(&tab)));
+        replay.apply_move(*mv);
+    }
+    s
+}
+
+/// Render a move list on one line as space-separated `Move::to_notation`
+/// tokens, e.g. `"D C3[2]->C5 W->F D D C1->F"`.
+///
+/// Unlike `render_move_sequence` this needs no tableau context, so it suits
+/// log files and bug reports; `parse_compact_notation` reads it back.
+pub fn render_compact_notation(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(|mv| mv.to_notation())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a `render_compact_notation` line: whitespace-separated tokens, each
+/// read with `Move::from_notation`. An empty (or all-whitespace) string
+/// gives an empty move list.
+pub fn parse_compact_notation(s: &str) -> Result<Vec<Move>, MoveParseError> {
+    s.split_whitespace().map(Move::from_notation).collect()
 }
 
 /// Print a concise summary of the face-up top card of each tableau column.
@@ -173,28 +646,33 @@ pub fn print_tableau(tab: &Tableau) {
 ///   Piles (playing edge):
 ///   C1: 4S  C2: 2H  C3: JS  C4: JD  C5: TC  C6: 7C  C7: 2D
 pub fn print_playing_edge(tab: &Tableau) {
+    write_playing_edge(tab, &mut io::stdout()).expect("failed to write to stdout");
+}
+
+/// `print_playing_edge`, writing to `w` instead of stdout.
+pub fn write_playing_edge(tab: &Tableau, w: &mut impl Write) -> io::Result<()> {
     use crate::tableau::NUM_COLS;
 
-    print!("Piles (playing edge): ");
+    write!(w, "Piles (playing edge): ")?;
     for col_idx in 0..NUM_COLS {
         let col = &tab.columns[col_idx];
         if col.len == 0 {
             // Empty column.
-            print!("C{}: --  ", col_idx + 1);
+            write!(w, "C{}: --  ", col_idx + 1)?;
             continue;
         }
         if col.len <= col.num_face_down {
             // Column has cards but all face-down (shouldn't happen in Klondike after deal,
             // but we handle it defensively).
-            print!("C{}: XX  ", col_idx + 1);
+            write!(w, "C{}: XX  ", col_idx + 1)?;
             continue;
         }
 
         let top_idx = (col.len - 1) as usize;
         let top_card = col.cards[top_idx];
-        print!("C{}: {:>2}  ", col_idx + 1, top_card.short_str());
+        write!(w, "C{}: {:>2}  ", col_idx + 1, top_card.short_str())?;
     }
-    println!();
+    writeln!(w)
 }
 
 /// Debug helper: print every pile with all cards shown (ignoring face-down).
@@ -203,44 +681,83 @@ pub fn print_playing_edge(tab: &Tableau) {
 /// - Stock: bottom -> top (as stored in `Pile`)
 /// - Waste: bottom -> top
 pub fn print_full_piles_debug(tab: &Tableau) {
+    print_full_piles_debug_with_order(tab, PileOrder::BottomToTop);
+}
+
+/// `print_full_piles_debug`, writing to `w` instead of stdout.
+pub fn write_full_piles_debug(tab: &Tableau, w: &mut impl Write) -> io::Result<()> {
+    write_full_piles_debug_with_order(tab, PileOrder::BottomToTop, w)
+}
+
+/// Order in which `print_full_piles_debug_with_order` lists the stock and
+/// waste.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PileOrder {
+    /// Storage order (`Pile::iter`): the top card comes last.
+    #[default]
+    BottomToTop,
+    /// Most recently pushed first (`Pile::iter_rev`).
+    TopToBottom,
+}
+
+/// `print_full_piles_debug`, listing the stock and waste in `order`.
+/// Columns are always shown bottom -> top; each stock/waste line ends with
+/// the direction used.
+pub fn print_full_piles_debug_with_order(tab: &Tableau, order: PileOrder) {
+    write_full_piles_debug_with_order(tab, order, &mut io::stdout()).expect("failed to write to stdout");
+}
+
+/// `print_full_piles_debug_with_order`, writing to `w` instead of stdout.
+pub fn write_full_piles_debug_with_order(
+    tab: &Tableau,
+    order: PileOrder,
+    w: &mut impl Write,
+) -> io::Result<()> {
     use crate::tableau::NUM_COLS;
 
-    println!("Full piles (all cards shown, bottom -> top within each pile):");
+    writeln!(w, "Full piles (all cards shown, columns bottom -> top):")?;
 
     // Columns
     for col_idx in 0..NUM_COLS {
         let col = &tab.columns[col_idx];
-        print!("  C{}: ", col_idx + 1);
+        write!(w, "  C{}: ", col_idx + 1)?;
         if col.len == 0 {
-            println!("<empty>");
+            writeln!(w, "<empty>")?;
         } else {
             for card in col.iter_all() {
-                print!("{} ", card.short_str());
+                write!(w, "{} ", card.short_str())?;
             }
-            println!();
+            writeln!(w)?;
         }
     }
 
-    // Stock
-    print!("  Stock: ");
-    if tab.stock.len() == 0 {
-        println!("<empty>");
-    } else {
-        for card in tab.stock.iter() {
-            print!("{} ", card.short_str());
-        }
-        println!("(bottom -> top)");
-    }
+    write_pile_debug(w, "Stock", &tab.stock, order)?;
+    write_pile_debug(w, "Waste", &tab.waste, order)
+}
 
-    // Waste
-    print!("  Waste: ");
-    if tab.waste.len() == 0 {
-        println!("<empty>");
-    } else {
-        for card in tab.waste.iter() {
-            print!("{} ", card.short_str());
+fn write_pile_debug<const N: usize>(
+    w: &mut impl Write,
+    name: &str,
+    pile: &Pile<N>,
+    order: PileOrder,
+) -> io::Result<()> {
+    write!(w, "  {}: ", name)?;
+    if pile.is_empty() {
+        return writeln!(w, "<empty>");
+    }
+    match order {
+        PileOrder::BottomToTop => {
+            for card in pile.iter() {
+                write!(w, "{} ", card.short_str())?;
+            }
+            writeln!(w, "(bottom -> top)")
+        }
+        PileOrder::TopToBottom => {
+            for card in pile.iter_rev() {
+                write!(w, "{} ", card.short_str())?;
+            }
+            writeln!(w, "(top -> bottom)")
         }
-        println!("(bottom -> top)");
     }
 }
 
@@ -299,16 +816,16 @@ mod tests {
         for (col_idx, col) in tab.columns.iter().enumerate() {
             let h = col.len as usize;
 
-            for row in 0..max_height {
+            for (row, cells) in grid.iter_mut().enumerate() {
                 if row >= h {
                     // No card at this row for this column.
-                    grid[row][col_idx] = String::new();
+                    cells[col_idx] = String::new();
                 } else {
                     // Row within the column, top-justified.
                     let idx = row; // 0..h-1 (top..bottom)
                     let card = col.cards[idx];
                     let face_down = (idx as u8) < col.num_face_down;
-                    grid[row][col_idx] = format_card_visible(card, !face_down);
+                    cells[col_idx] = format_card_visible(card, !face_down);
                 }
             }
         }
@@ -331,16 +848,19 @@ mod tests {
         let mut grid = vec![vec![String::new(); NUM_COLS]; max_height];
 
         let base_offset = 6; // "      " at line start
-        for (row_idx, line) in body.iter().enumerate() {
-            for col_idx in 0..NUM_COLS {
+        for (cells, line) in grid.iter_mut().zip(body.iter()) {
+            // Index by character, not byte: with `unicode-suits` a suit
+            // symbol is one column wide but several bytes long.
+            let line: Vec<char> = line.chars().collect();
+            for (col_idx, cell_out) in cells.iter_mut().enumerate() {
                 let start = base_offset + 4 * col_idx;
                 if start >= line.len() {
-                    grid[row_idx][col_idx] = String::new();
+                    *cell_out = String::new();
                     continue;
                 }
                 let end = (start + 4).min(line.len());
-                let cell = &line[start..end];
-                grid[row_idx][col_idx] = cell.trim().to_string();
+                let cell: String = line[start..end].iter().collect();
+                *cell_out = cell.trim().to_string();
             }
         }
 
@@ -437,6 +957,73 @@ mod tests {
         assert!(line.contains("(2 cards)"));
     }
 
+    #[test]
+    fn indexed_columns_number_each_row() {
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+        let plain = render_columns_with_mode(&tab, ColorMode::NoColor);
+        let indexed = render_column_grid(&tab, ColorMode::NoColor, true);
+        println!("{}", indexed);
+
+        let rows: Vec<&str> = indexed.lines().collect();
+        assert!(rows[2].starts_with("   0: "), "first data row: {:?}", rows[2]);
+        assert!(indexed.contains("  0:"));
+        assert!(rows[8].starts_with("   6: "));
+        // Only the prefix differs; the grid itself is the same.
+        for (a, b) in plain.lines().zip(indexed.lines()).skip(2) {
+            assert_eq!(&a[6..], &b[6..]);
+        }
+        assert!(!plain.contains("0:"));
+        assert_eq!(DisplayConfig::default().color_mode, ColorMode::NoColor);
+        assert!(!DisplayConfig::default().show_row_indices);
+    }
+
+    #[test]
+    fn stock_peek_shows_top_waste_cards_newest_first() {
+        let mut tab = Tableau::new_empty();
+        let line = render_stock_peek_with_mode(&tab, 3, ColorMode::NoColor);
+        assert_eq!(line, "Stock: [empty]    Waste: [empty]");
+
+        let dealt = [
+            Card::new(Suit::Spades, Rank::Three),
+            Card::new(Suit::Diamonds, Rank::Four),
+        ];
+        for c in dealt {
+            tab.waste.push(c);
+        }
+        tab.stock.push(Card::new(Suit::Clubs, Rank::Nine));
+
+        let line = render_stock_peek_with_mode(&tab, 0, ColorMode::NoColor);
+        println!("n=0, waste of 2: {}", line);
+        assert_eq!(line, "Stock: [1 cards]    Waste: (2 cards)");
+
+        let line = render_stock_peek_with_mode(&tab, 3, ColorMode::NoColor);
+        println!("n=3, waste of 2: {}", line);
+        assert_eq!(
+            line,
+            format!("Stock: [1 cards]    Waste: >{} {} (2 cards)", dealt[1].short_str(), dealt[0].short_str())
+        );
+
+        let more = [
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Clubs, Rank::Three),
+            Card::new(Suit::Diamonds, Rank::Seven),
+        ];
+        for c in more {
+            tab.waste.push(c);
+        }
+        let line = render_stock_peek_with_mode(&tab, 3, ColorMode::NoColor);
+        println!("n=3, waste of 5: {}", line);
+        assert_eq!(
+            line,
+            format!(
+                "Stock: [1 cards]    Waste: >{} {} {} (5 cards)",
+                more[2].short_str(),
+                more[1].short_str(),
+                more[0].short_str()
+            )
+        );
+    }
+
     /// Test 3: foundations rendering shows only the top card per pile, and the
     /// mapping matches internal foundation rank numbers, without hard-coding
     /// specific ranks.
@@ -477,4 +1064,303 @@ mod tests {
             assert!(line.contains(&top));
         }
     }
+
+    /// `NoColor` output must stay byte-identical to the original plain
+    /// renderer (golden string captured before color support was added).
+    #[cfg(not(feature = "unicode-suits"))]
+    #[test]
+    fn no_color_output_is_plain_golden_text() {
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+        let expected = "\
+Foundations: [  ] [  ] [  ] [  ]
+Stock: [24 cards]    Waste: [empty]
+
+Columns:
+       C1  C2  C3  C4  C5  C6  C7 
+       2S  XX  XX  XX  XX  XX  XX 
+           AS  XX  XX  XX  XX  XX 
+               KC  XX  XX  XX  XX 
+                   QC  XX  XX  XX 
+                       JC  XX  XX 
+                           TC  XX 
+                               9C 
+";
+        assert_eq!(render_tableau_with_mode(&tab, ColorMode::NoColor), expected);
+    }
+
+    /// Strip ANSI SGR escape sequences (`ESC [ ... m`).
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c2 in chars.by_ref() {
+                    if c2 == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn color_output_wraps_cards_and_keeps_layout() {
+        let mut deck = standard_deck();
+        shuffle_deck(&mut deck, 42);
+        let mut tab = Tableau::deal_from_shuffled(deck);
+        tab.foundations = [3, 0, 1, 13];
+        tab.waste.push(Card::new(Suit::Hearts, Rank::King));
+
+        let plain = render_tableau_with_mode(&tab, ColorMode::NoColor);
+        let colored = render_tableau_with_mode(&tab, ColorMode::Color);
+        println!("{}", colored);
+
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(strip_ansi(&colored), plain);
+
+        let kh = Card::new(Suit::Hearts, Rank::King);
+        let as_ = Card::new(Suit::Spades, Rank::Ace);
+        assert_eq!(
+            format_card_visible_with_mode(kh, true, ColorMode::Color),
+            format!("\x1b[31m{}\x1b[0m", kh.short_str())
+        );
+        assert_eq!(format_card_visible_with_mode(as_, true, ColorMode::Color), as_.short_str());
+        assert_eq!(
+            format_card_visible_with_mode(as_, false, ColorMode::Color),
+            "\x1b[90mXX\x1b[0m"
+        );
+        assert!(colored.contains(&format!("Waste: >\x1b[31m{}\x1b[0m (1 cards)", kh.short_str())));
+    }
+
+    #[test]
+    fn move_sequence_is_numbered_and_described_in_context() {
+        let deck = standard_deck();
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        let mut expected = Vec::new();
+        for _ in 0..5 {
+            let mv = crate::moves::generate_legal_moves(&game.tableau)[0];
+            expected.push(mv.describe(&game.tableau));
+            game.apply_move(mv);
+        }
+
+        let text = render_move_sequence(deck, &game.moves, DrawRule::Draw3);
+        println!("{}", text);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(text.ends_with('\n'));
+        for (i, (line, desc)) in lines.iter().zip(&expected).enumerate() {
+            assert_eq!(*line, format!("  {:3}: {}", i + 1, desc));
+        }
+
+        let deals = vec![Move { kind: crate::moves::MoveKind::DealFromStock { cards_drawn: 1 } }; 2];
+        assert_eq!(
+            render_move_sequence(deck, &deals, DrawRule::Draw1),
+            "    1: Deal from Stock (drew 1 card)\n    2: Deal from Stock (drew 1 card)\n"
+        );
+
+        let verbose = render_move_sequence_verbose(deck, &game.moves, DrawRule::Draw3);
+        assert!(verbose.starts_with(&render_tableau(&Tableau::deal_from_shuffled(deck))));
+        for line in &lines {
+            assert!(verbose.contains(line));
+        }
+    }
+
+    #[test]
+    fn html_tableau_is_a_table_fragment_with_card_classes() {
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+        let html = render_tableau_html(&tab);
+        println!("{}", html);
+
+        assert!(html.starts_with("<table"));
+        assert!(html.contains("<table class=\"klondike\">"));
+        assert!(html.trim_end().ends_with("</table>"));
+        assert!(!html.contains("<html") && !html.contains("<body"));
+
+        // The standard deck's deal leaves AH out of sight (XX or in the
+        // stock), so it only shows up once played to its foundation.
+        assert!(!html.contains("AH"));
+        let mut played = tab;
+        played.foundations[0] = 1;
+        assert!(render_tableau_html(&played).contains("<td class=\"card red\">AH</td>"));
+
+        for col in &tab.columns {
+            let top = col.top().unwrap();
+            let color = if top.color() == 'R' { "red" } else { "black" };
+            assert!(html.contains(&format!("<td class=\"card {}\">{}</td>", color, top.ascii_str())));
+        }
+        assert_eq!(html.matches("card hidden").count(), 21);
+        assert_eq!(html.matches("<th>").count(), NUM_COLS);
+
+        // Escaping: every '&' starts an entity.
+        assert_eq!(escape_html("a & <b> \"c\""), "a &amp; &lt;b&gt; &quot;c&quot;");
+        for (i, _) in html.match_indices('&') {
+            let rest = &html[i..];
+            assert!(["&amp;", "&lt;", "&gt;", "&quot;", "&#39;"].iter().any(|e| rest.starts_with(e)));
+        }
+    }
+
+    #[test]
+    fn compact_notation_round_trips() {
+        use crate::moves::{generate_legal_moves, MoveKind};
+
+        // Empty.
+        assert_eq!(render_compact_notation(&[]), "");
+        assert_eq!(parse_compact_notation(""), Ok(Vec::new()));
+        assert_eq!(parse_compact_notation("  \t "), Ok(Vec::new()));
+
+        // Single move.
+        let deal = Move { kind: MoveKind::DealFromStock { cards_drawn: 3 } };
+        assert_eq!(render_compact_notation(&[deal]), "D");
+        assert_eq!(parse_compact_notation("D"), Ok(vec![deal]));
+
+        // 20 moves actually played from the standard deal.
+        let mut game = GameState::new(standard_deck(), DrawRule::Draw3);
+        let mut moves = Vec::new();
+        for i in 0..20 {
+            let legal = generate_legal_moves(&game.current_tableau());
+            let mv = legal[i % legal.len()];
+            game.apply_move(mv);
+            moves.push(mv);
+        }
+        let line = render_compact_notation(&moves);
+        println!("20-move compact line: {}", line);
+        assert_eq!(line.split(' ').count(), 20);
+        assert_eq!(parse_compact_notation(&line), Ok(moves));
+
+        // A bad token reports the notation error.
+        assert!(parse_compact_notation("D C9->F").is_err());
+    }
+
+    #[test]
+    fn print_full_piles_debug_in_both_orders() {
+        print_run_hint();
+        let mut tab = Tableau::deal_from_shuffled(standard_deck());
+        Move::deal_from_stock(&tab).apply(&mut tab);
+        print_full_piles_debug_with_order(&tab, PileOrder::BottomToTop);
+        print_full_piles_debug_with_order(&tab, PileOrder::TopToBottom);
+
+        let render = |order| {
+            let mut buf: Vec<u8> = Vec::new();
+            write_full_piles_debug_with_order(&tab, order, &mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        let up = render(PileOrder::BottomToTop);
+        let down = render(PileOrder::TopToBottom);
+        let line = |text: &str, name: &str| -> String {
+            let prefix = format!("  {}: ", name);
+            text.lines().find_map(|l| l.strip_prefix(prefix.as_str())).unwrap().to_string()
+        };
+        let listed = |cards: Vec<&Card>, dir: &str| -> String {
+            let mut out: String = cards.iter().map(|c| format!("{} ", c.short_str())).collect();
+            out.push_str(dir);
+            out
+        };
+
+        for (name, pile) in [("Stock", &tab.stock), ("Waste", &tab.waste)] {
+            assert!(!pile.is_empty());
+            assert_eq!(line(&up, name), listed(pile.iter().collect(), "(bottom -> top)"));
+            assert_eq!(line(&down, name), listed(pile.iter_rev().collect(), "(top -> bottom)"));
+        }
+        // Columns are always bottom -> top, whatever the pile order.
+        let columns = |text: &str| -> Vec<String> {
+            text.lines().filter(|l| l.starts_with("  C")).map(str::to_string).collect()
+        };
+        assert_eq!(columns(&up).len(), 7);
+        assert_eq!(columns(&up), columns(&down));
+    }
+
+    #[test]
+    fn tableau_diff_shows_dealt_cards_moving_to_waste() {
+        let before = Tableau::deal_from_shuffled(standard_deck());
+        assert_eq!(render_tableau_diff(&before, &before), "");
+
+        let mut after = before;
+        Move::deal_from_stock(&after).apply(&mut after);
+        let diff = render_tableau_diff(&before, &after);
+        println!("{}", diff);
+
+        let cards_on = |prefix: &str| -> Vec<String> {
+            diff.lines()
+                .filter_map(|l| l.strip_prefix(prefix))
+                .flat_map(|l| l.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+                .collect()
+        };
+        let removed = cards_on("- Stock:");
+        let mut added = cards_on("+ Waste:");
+        assert_eq!(removed.len(), 3);
+        assert_eq!(diff.lines().filter(|l| l.starts_with('-')).count(), 1);
+        assert_eq!(diff.lines().filter(|l| l.starts_with('+')).count(), 1);
+        added.sort();
+        let mut removed = removed;
+        removed.sort();
+        assert_eq!(added, removed);
+
+        // A stock with fewer than 3 cards moves only what it has.
+        let mut short = before;
+        short.stock.len = 2;
+        let mut dealt = short;
+        Move::deal_from_stock(&dealt).apply(&mut dealt);
+        let diff = render_tableau_diff(&short, &dealt);
+        assert!(diff.contains("~ Stock: 2 -> 0 cards"), "{}", diff);
+        assert!(diff.contains("~ Waste: 0 -> 2 cards"), "{}", diff);
+    }
+
+    #[test]
+    fn write_functions_fill_a_buffer() {
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+
+        let mut buf: Vec<u8> = Vec::new();
+        write_tableau(&tab, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(text, format!("{}\n", render_tableau(&tab)));
+        assert!(text.contains("Stock:"));
+
+        let mut buf: Vec<u8> = Vec::new();
+        write_playing_edge(&tab, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.starts_with("Piles (playing edge): C1: "));
+        assert!(text.contains("C7: "));
+        assert!(text.ends_with('\n'));
+
+        let mut buf: Vec<u8> = Vec::new();
+        write_full_piles_debug(&tab, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.starts_with("Full piles (all cards shown, columns bottom -> top):\n"));
+        assert!(text.contains("  Stock: "));
+        assert!(text.contains("  Waste: <empty>\n"));
+        assert_eq!(text.lines().count(), 1 + NUM_COLS + 2);
+    }
+
+    #[test]
+    fn compact_tableau_line_for_fresh_deal() {
+        const FRESH_STANDARD_DECK: &str = "F:{0,0,0,0} S:24 W:0 C:[1>2S,2>AS,3>KC,4>QC,5>JC,6>TC,7>9C]";
+        let mut tab = Tableau::deal_from_shuffled(standard_deck());
+        assert_eq!(render_tableau_compact(&tab), FRESH_STANDARD_DECK);
+
+        Move::deal_from_stock(&tab).apply(&mut tab);
+        let line = render_tableau_compact(&tab);
+        println!("{}", line);
+        let waste_top = tab.waste.top().unwrap().ascii_str();
+        assert!(line.starts_with(&format!("F:{{0,0,0,0}} S:21 W:3>{} C:[", waste_top)));
+        assert_eq!(line.split(' ').count(), 4);
+    }
+
+    #[test]
+    fn foundations_verbose_lists_every_built_card() {
+        let mut tab = Tableau::new_empty();
+        tab.foundations = [3, 2, 0, 1];
+        let s = render_foundations_verbose(&tab);
+        println!("{}", s);
+        assert!(s.contains("Hearts: [AH 2H 3H]"));
+        assert!(s.contains("Clubs: [AC 2C]"));
+        assert!(s.contains("Spades: []"));
+        assert!(s.contains("Diamonds: [AD]"));
+        assert_eq!(s, "Hearts: [AH 2H 3H] Clubs: [AC 2C] Spades: [] Diamonds: [AD]");
+
+        tab.foundations = [13; 4];
+        assert!(render_foundations_verbose(&tab).contains("[AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC]"));
+    }
 }
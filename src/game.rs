@@ -7,10 +7,25 @@
 //!   - the current tableau (logically derivable from deck + moves, but
 //!     cached here for convenience and speed)
 //!   - a 64-bit hash of the current tableau for fast loop detection.
+//!
+//! The cached hash is a Zobrist hash: the XOR of one precomputed random
+//! key per (card, location) pair, so `apply_move` can update it by XOR-ing
+//! out the moved cards' old keys and XOR-ing in their new ones instead of
+//! rehashing the whole tableau.
+
+use core::fmt;
+use std::sync::OnceLock;
+#[cfg(feature = "serde")]
+use std::{fs, io, path::Path};
 
 use crate::card::{Card, CARDS_PER_DECK};
-use crate::moves::Move;
-use crate::tableau::{Tableau, NUM_COLS};
+#[cfg(feature = "serde")]
+use crate::parse::LoadError;
+use crate::parse::ParseError;
+use crate::moves::{generate_foundation_to_column_moves, generate_legal_moves, Move, MoveKind};
+use crate::tableau::{
+    DrawRule, Tableau, MAX_COL, MAX_STOCK, MAX_WASTE, NUM_COLS, NUM_FOUNDATIONS,
+};
 
 /// Why a search over this game may have stopped.
 ///
@@ -27,6 +42,8 @@ pub enum TerminationReason {
     MaxNodesReached,
     /// The search stopped because a configured depth limit was hit.
     MaxDepthReached,
+    /// The search stopped because its wall-clock time limit ran out.
+    TimeLimitReached,
     /// The last branch could only generate already-visited tableaus, so
     /// it was pruned entirely by loop detection.
     LoopOnLastBranch,
@@ -63,52 +80,330 @@ fn fnv1a_mix_tag(h: u64, tag: u8) -> u64 {
 /// The exact layout is an implementation detail, but for any given
 /// tableau the hash will be deterministic. Collisions are possible in
 /// theory but extremely unlikely in practice.
+///
+/// `GameState` no longer uses this hash (see `ZobristTable`); it is kept as
+/// an independent, from-scratch hash for callers that want one.
 pub fn hash_tableau64(tab: &Tableau) -> u64 {
-    let mut h = FNV_OFFSET_BASIS;
+    tab.compute_hash()
+}
+
+impl Tableau {
+    /// Method form of `hash_tableau64`: the FNV-1a hash of the full
+    /// tableau (foundations, stock, waste, and every column card).
+    ///
+    /// ```
+    /// use klondike_chat::card::shuffled_deck_from_seed;
+    /// use klondike_chat::game::hash_tableau64;
+    /// use klondike_chat::tableau::Tableau;
+    ///
+    /// let tab = Tableau::deal_from_shuffled(shuffled_deck_from_seed(1));
+    /// assert_eq!(tab.compute_hash(), hash_tableau64(&tab));
+    /// ```
+    pub fn compute_hash(&self) -> u64 {
+        let mut h = FNV_OFFSET_BASIS;
+
+        // --- Foundations ---
+        h = fnv1a_mix_tag(h, 0xF0);
+        for &f in &self.foundations {
+            h = fnv1a_mix_byte(h, f);
+        }
+
+        // --- Stock ---
+        //
+        // We want to include the exact sequence of cards in the stock. We do
+        // this by working on a local copy of the tableau and draining the
+        // stock via `pop()`; this relies only on the public API and does not
+        // mutate the original tableau.
+        let mut tmp = *self;
+
+        h = fnv1a_mix_tag(h, b'S'); // tag for stock
+        while let Some(card) = tmp.stock.pop() {
+            // Pop order is deterministic (top-to-bottom). We do not care
+            // whether this is bottom-to-top or top-to-bottom as long as it
+            // is consistent across calls.
+            h = fnv1a_mix_byte(h, card.0);
+        }
+
+        // --- Waste ---
+        h = fnv1a_mix_tag(h, b'W'); // tag for waste
+        while let Some(card) = tmp.waste.pop() {
+            h = fnv1a_mix_byte(h, card.0);
+        }
+
+        // --- Columns ---
+        h = fnv1a_mix_tag(h, 0xC0);
+        for col_idx in 0..NUM_COLS {
+            let col = &self.columns[col_idx];
+            // Encode structure of the column.
+            h = fnv1a_mix_byte(h, col.len);
+            h = fnv1a_mix_byte(h, col.num_face_down);
+            // Encode all cards in this column, top-to-bottom.
+            let len = col.len as usize;
+            for i in 0..len {
+                h = fnv1a_mix_byte(h, col.cards[i].0);
+            }
+        }
+
+        h
+    }
+
+    /// Full Zobrist hash of this tableau under `table`; with
+    /// `zobrist_table()` this is the value `GameState::tableau_hash` holds.
+    pub fn compute_hash_with_table(&self, table: &ZobristTable) -> u64 {
+        table.hash_tableau(self)
+    }
+}
+
+/// Number of ranks in each foundation pile.
+const RANKS_PER_SUIT: usize = 13;
+
+/// Fixed seed for the Zobrist key generator, so hashes are reproducible
+/// from run to run (handy when comparing debug traces).
+const ZOBRIST_SEED: u64 = 0x4b4c_4f4e_4449_4b45; // "KLONDIKE"
+
+/// SplitMix64 step: a small, well-mixed 64-bit generator, good enough for
+/// filling a Zobrist table without an external RNG crate.
+#[inline]
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Zobrist keys for every place a card can sit in a tableau.
+///
+/// Locations are:
+///   - tableau columns, by column, depth and face-down / face-up,
+///   - stock and waste, by depth,
+///   - foundations, by suit and rank.
+///
+/// Depth is counted from the *bottom* of a pile or column (array index in
+/// `tableau.rs`), so pushing or popping at the top never changes the key
+/// of any card left in place.
+///
+/// `GameState` uses the process-wide table from `zobrist_table()`; build
+/// another with `new_deterministic` only if you need an owned copy. Every
+/// table holds the same keys, since they all come from one fixed seed.
+pub struct ZobristTable {
+    /// `[card][col][depth][face_up]`, flattened.
+    column: Vec<u64>,
+    /// `[card][depth]`, flattened.
+    stock: Vec<u64>,
+    /// `[card][depth]`, flattened.
+    waste: Vec<u64>,
+    /// `[foundation][rank_index]`; the card is implied by its position.
+    foundation: [[u64; RANKS_PER_SUIT]; NUM_FOUNDATIONS],
+}
+
+impl ZobristTable {
+    /// Build the table by drawing every key, in a fixed order, from a
+    /// SplitMix64 generator seeded with a fixed constant.
+    pub fn new_deterministic() -> Self {
+        let mut state = ZOBRIST_SEED;
+        let n_cards = CARDS_PER_DECK as usize;
+
+        let column = (0..n_cards * NUM_COLS * MAX_COL * 2)
+            .map(|_| splitmix64(&mut state))
+            .collect();
+        let stock = (0..n_cards * MAX_STOCK)
+            .map(|_| splitmix64(&mut state))
+            .collect();
+        let waste = (0..n_cards * MAX_WASTE)
+            .map(|_| splitmix64(&mut state))
+            .collect();
+        let mut foundation = [[0u64; RANKS_PER_SUIT]; NUM_FOUNDATIONS];
+        for pile in foundation.iter_mut() {
+            for key in pile.iter_mut() {
+                *key = splitmix64(&mut state);
+            }
+        }
+
+        ZobristTable {
+            column,
+            stock,
+            waste,
+            foundation,
+        }
+    }
+
+    #[inline]
+    fn column_key(&self, card: Card, col: usize, depth: usize, face_up: bool) -> u64 {
+        let slot = (card.0 as usize * NUM_COLS + col) * MAX_COL + depth;
+        self.column[slot * 2 + face_up as usize]
+    }
+
+    #[inline]
+    fn stock_key(&self, card: Card, depth: usize) -> u64 {
+        self.stock[card.0 as usize * MAX_STOCK + depth]
+    }
 
-    // --- Foundations ---
-    h = fnv1a_mix_tag(h, 0xF0);
-    for &f in &tab.foundations {
-        h = fnv1a_mix_byte(h, f);
+    #[inline]
+    fn waste_key(&self, card: Card, depth: usize) -> u64 {
+        self.waste[card.0 as usize * MAX_WASTE + depth]
     }
 
-    // --- Stock ---
-    //
-    // We want to include the exact sequence of cards in the stock. We do
-    // this by working on a local copy of the tableau and draining the
-    // stock via `pop()`; this relies only on the public API and does not
-    // mutate the original tableau.
-    let mut tmp = *tab;
+    /// Full Zobrist hash of a tableau, computed from scratch.
+    pub fn hash_tableau(&self, tab: &Tableau) -> u64 {
+        let mut h = 0u64;
+
+        for (f_idx, &rank) in tab.foundations.iter().enumerate() {
+            for r_idx in 0..rank as usize {
+                h ^= self.foundation[f_idx][r_idx];
+            }
+        }
+        for (depth, &card) in tab.stock.iter().enumerate() {
+            h ^= self.stock_key(card, depth);
+        }
+        for (depth, &card) in tab.waste.iter().enumerate() {
+            h ^= self.waste_key(card, depth);
+        }
+        for (c, col) in tab.columns.iter().enumerate() {
+            for (depth, &card) in col.iter_all().enumerate() {
+                let face_up = depth >= col.num_face_down as usize;
+                h ^= self.column_key(card, c, depth, face_up);
+            }
+        }
 
-    h = fnv1a_mix_tag(h, b'S'); // tag for stock
-    while let Some(card) = tmp.stock.pop() {
-        // Pop order is deterministic (top-to-bottom). We do not care
-        // whether this is bottom-to-top or top-to-bottom as long as it
-        // is consistent across calls.
-        h = fnv1a_mix_byte(h, card.0);
+        h
     }
 
-    // --- Waste ---
-    h = fnv1a_mix_tag(h, b'W'); // tag for waste
-    while let Some(card) = tmp.waste.pop() {
-        h = fnv1a_mix_byte(h, card.0);
+    /// Update `hash` (the hash of `before`) for `mv`, where `after` is
+    /// `before` with `mv` applied.
+    ///
+    /// Only the cards the move actually touches are re-keyed, plus any
+    /// card flipped face-up when a column's last face-up card leaves. The
+    /// number of cards moved is read off the two tableaus rather than
+    /// assumed, so a move that `Move::apply` ignores leaves `hash` alone.
+    pub fn update_hash(
+        &self,
+        mut hash: u64,
+        before: &Tableau,
+        after: &Tableau,
+        mv: Move,
+    ) -> u64 {
+        match mv.kind {
+            MoveKind::ColumnToColumn {
+                src_col,
+                src_index,
+                dst_col,
+            } => {
+                let (s, d) = (src_col as usize, dst_col as usize);
+                let moved = (before.columns[s].len - after.columns[s].len) as usize;
+                let start = src_index as usize;
+                let dst_len = before.columns[d].len as usize;
+                for k in 0..moved {
+                    let card = before.columns[s].cards[start + k];
+                    hash ^= self.column_key(card, s, start + k, true);
+                    hash ^= self.column_key(card, d, dst_len + k, true);
+                }
+                hash ^= self.flip_delta(before, after, s);
+            }
+
+            MoveKind::ColumnToFoundation { src_col } => {
+                let s = src_col as usize;
+                let col = &before.columns[s];
+                if col.len > after.columns[s].len {
+                    let depth = col.len as usize - 1;
+                    hash ^= self.column_key(col.cards[depth], s, depth, true);
+                }
+                hash ^= self.flip_delta(before, after, s);
+            }
+
+            MoveKind::WasteToColumn { dst_col } => {
+                let d = dst_col as usize;
+                if let Some(card) = before.waste.top()
+                    && after.waste.len < before.waste.len
+                {
+                    hash ^= self.waste_key(card, before.waste.len as usize - 1);
+                    hash ^= self.column_key(card, d, before.columns[d].len as usize, true);
+                }
+            }
+
+            MoveKind::WasteToFoundation => {
+                if let Some(card) = before.waste.top()
+                    && after.waste.len < before.waste.len
+                {
+                    hash ^= self.waste_key(card, before.waste.len as usize - 1);
+                }
+            }
+
+            MoveKind::FoundationToColumn {
+                foundation_idx,
+                dst_col,
+            } => {
+                let (f, d) = (foundation_idx as usize, dst_col as usize);
+                if after.foundations[f] < before.foundations[f] {
+                    let depth = before.columns[d].len as usize;
+                    hash ^= self.column_key(after.columns[d].cards[depth], d, depth, true);
+                }
+            }
+
+            MoveKind::FlipColumn { col } => {
+                hash ^= self.flip_delta(before, after, col as usize);
+            }
+
+            MoveKind::DealFromStock { .. } => {
+                let stock_len = before.stock.len as usize;
+                let waste_len = before.waste.len as usize;
+                let drawn = stock_len - after.stock.len as usize;
+                for k in 0..drawn {
+                    let depth = stock_len - 1 - k;
+                    let card = before.stock.cards[depth];
+                    hash ^= self.stock_key(card, depth);
+                    hash ^= self.waste_key(card, waste_len + k);
+                }
+            }
+
+            MoveKind::RedealStock => {
+                let stock_len = before.stock.len as usize;
+                let waste_len = before.waste.len as usize;
+                for (depth, &card) in before.waste.iter().enumerate() {
+                    hash ^= self.waste_key(card, depth);
+                    hash ^= self.stock_key(card, stock_len + waste_len - 1 - depth);
+                }
+            }
+        }
+
+        // Cards arriving on or leaving the foundations (never more than
+        // one per move).
+        for f_idx in 0..NUM_FOUNDATIONS {
+            let (b, a) = (before.foundations[f_idx], after.foundations[f_idx]);
+            for r_idx in b.min(a)..b.max(a) {
+                hash ^= self.foundation[f_idx][r_idx as usize];
+            }
+        }
+
+        hash
     }
 
-    // --- Columns ---
-    h = fnv1a_mix_tag(h, 0xC0);
-    for col_idx in 0..NUM_COLS {
-        let col = &tab.columns[col_idx];
-        // Encode structure of the column.
-        h = fnv1a_mix_byte(h, col.len);
-        h = fnv1a_mix_byte(h, col.num_face_down);
-        // Encode all cards in this column, top-to-bottom.
-        let len = col.len as usize;
-        for i in 0..len {
-            h = fnv1a_mix_byte(h, col.cards[i].0);
+    /// XOR delta for cards in column `c` that went from face-down to
+    /// face-up between `before` and `after`.
+    fn flip_delta(&self, before: &Tableau, after: &Tableau, c: usize) -> u64 {
+        let col = &after.columns[c];
+        let mut delta = 0u64;
+        for depth in col.num_face_down..before.columns[c].num_face_down {
+            let card = col.cards[depth as usize];
+            delta ^= self.column_key(card, c, depth as usize, false);
+            delta ^= self.column_key(card, c, depth as usize, true);
         }
+        delta
     }
+}
 
-    h
+/// The process-wide Zobrist table, built on first use.
+pub fn zobrist_table() -> &'static ZobristTable {
+    static TABLE: OnceLock<ZobristTable> = OnceLock::new();
+    TABLE.get_or_init(ZobristTable::new_deterministic)
+}
+
+/// Full Zobrist hash of `tab` under `table`; the same value
+/// `GameState::tableau_hash` holds when `table` is `zobrist_table()`.
+///
+/// Free-function counterpart of `hash_tableau64`, for comparing the two.
+pub fn hash_tableau_zobrist(tab: &Tableau, table: &ZobristTable) -> u64 {
+    table.hash_tableau(tab)
 }
 
 /// Complete description of a single game's state at a point in time.
@@ -126,10 +421,20 @@ pub struct GameState {
     /// The current tableau, obtained by dealing `initial_deck` and applying
     /// all moves in `moves` in order.
     pub tableau: Tableau,
-    /// The sequence of moves applied from the initial tableau to this position.
-    pub moves: Vec<Move>,
-    /// 64-bit hash of the current tableau, for fast loop detection.
+    /// The sequence of moves applied from the initial tableau to this
+    /// position. Read it with `move_stack`; change it only through
+    /// `apply_move` / `pop_move` so the cached tableau stays in step.
+    pub(crate) moves: Vec<Move>,
+    /// 64-bit Zobrist hash of the current tableau, for fast loop detection.
     pub tableau_hash: u64,
+    /// Number of `RedealStock` moves in `moves`, so searches can enforce
+    /// `SearchLimits::max_redeals` without rescanning the move stack.
+    pub redeals_used: u8,
+    /// Number of times the stock has been fully cycled (dealt out, then
+    /// turned back over): one per `RedealStock` in `moves`. Unlike
+    /// `redeals_used` this does not saturate at 255. Searches cap it with
+    /// `SearchLimits::max_stock_cycles`.
+    pub stock_cycle_count: u32,
     /// If this state represents the end of a search, records why the search
     /// stopped there. For interior nodes in the search tree this will
     /// normally be `None`.
@@ -137,15 +442,18 @@ pub struct GameState {
 }
 
 impl GameState {
-    /// Create a new game state from an initial deck with no moves played.
-    pub fn new(initial_deck: [Card; CARDS_PER_DECK as usize]) -> Self {
-        let tableau = Tableau::deal_from_shuffled(initial_deck);
-        let tableau_hash = hash_tableau64(&tableau);
+    /// Create a new game state from an initial deck with no moves played,
+    /// using the given draw rule for every later `DealFromStock`.
+    pub fn new(initial_deck: [Card; CARDS_PER_DECK as usize], draw_rule: DrawRule) -> Self {
+        let tableau = Tableau::deal_from_shuffled_with_rule(initial_deck, draw_rule);
+        let tableau_hash = tableau.compute_hash_with_table(zobrist_table());
         GameState {
             initial_deck,
             tableau,
             moves: Vec::new(),
             tableau_hash,
+            redeals_used: 0,
+            stock_cycle_count: 0,
             termination_reason: None,
         }
     }
@@ -157,17 +465,22 @@ impl GameState {
     pub fn from_parts(
         initial_deck: [Card; CARDS_PER_DECK as usize],
         moves: Vec<Move>,
+        draw_rule: DrawRule,
     ) -> Self {
-        let mut tableau = Tableau::deal_from_shuffled(initial_deck);
+        let mut tableau = Tableau::deal_from_shuffled_with_rule(initial_deck, draw_rule);
         for mv in &moves {
             mv.apply(&mut tableau);
         }
-        let tableau_hash = hash_tableau64(&tableau);
+        let tableau_hash = tableau.compute_hash_with_table(zobrist_table());
+        let redeals_used = count_redeals(&moves);
+        let stock_cycle_count = count_stock_cycles(&moves);
         GameState {
             initial_deck,
             tableau,
             moves,
             tableau_hash,
+            redeals_used,
+            stock_cycle_count,
             termination_reason: None,
         }
     }
@@ -177,21 +490,302 @@ impl GameState {
         self.moves.len()
     }
 
+    /// Draw rule this game is being played under.
+    pub fn draw_rule(&self) -> DrawRule {
+        self.tableau.draw_rule
+    }
+
     /// Whether no moves have yet been applied.
     pub fn is_at_initial(&self) -> bool {
         self.moves.is_empty()
     }
 
+    /// Whether the current tableau is won (`Tableau::is_win`).
+    #[inline]
+    pub fn is_win(&self) -> bool {
+        self.tableau.is_win()
+    }
+
+    /// Copy of the foundation ranks (`Tableau::foundations`), in
+    /// `Suit::ALL` order: 0 for empty, 13 for complete.
+    pub fn foundation_progress(&self) -> [u8; NUM_FOUNDATIONS] {
+        self.tableau.foundations
+    }
+
+    /// The move at `index` in the move stack (0 = first move played), or
+    /// `None` past the end.
+    pub fn move_at(&self, index: usize) -> Option<&Move> {
+        self.moves.get(index)
+    }
+
+    /// The whole move stack, first move played first.
+    pub fn move_stack(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// The most recently applied move, without undoing it; `None` at the
+    /// initial deal.
+    pub fn peek_last_move(&self) -> Option<&Move> {
+        self.moves.last()
+    }
+
     /// Apply a move to this game state:
     ///   - mutate the cached tableau using `Move::apply`
-    ///   - append the move to the move stack
-    ///   - recompute the tableau hash
+    ///   - append the move to the move stack (a `DealFromStock` is recorded
+    ///     with the number of cards it actually drew)
+    ///   - update the tableau hash incrementally for the cards that moved
+    ///   - count the move in `redeals_used` and `stock_cycle_count` if it
+    ///     is a `RedealStock`
     ///
     /// This is the primary way regular code should advance the game state.
     pub fn apply_move(&mut self, mv: Move) {
+        let before = self.tableau;
+        let mv = mv.with_deal_count_for(&before);
         mv.apply(&mut self.tableau);
         self.moves.push(mv);
-        self.tableau_hash = hash_tableau64(&self.tableau);
+        if matches!(mv.kind, MoveKind::RedealStock) {
+            self.redeals_used = self.redeals_used.saturating_add(1);
+            self.stock_cycle_count = self.stock_cycle_count.saturating_add(1);
+        }
+        self.tableau_hash =
+            zobrist_table().update_hash(self.tableau_hash, &before, &self.tableau, mv);
+
+        // Debug-time consistency check, including the incremental hash
+        // against a full recompute. The move-history checks are skipped:
+        // they are O(moves) per call, which makes a debug-build DFS
+        // quadratic, and they hold by induction when the state before this
+        // move was consistent.
+        self.assert_cached_state_consistent();
+    }
+
+    /// Return the child state reached by playing `mv` from this one, leaving
+    /// `self` untouched (clone + `apply_move`).
+    ///
+    /// In debug builds this asserts that `mv` is legal here: listed by
+    /// `generate_legal_moves`, or a relaxed-rule `FoundationToColumn` move
+    /// listed by `generate_foundation_to_column_moves`.
+    pub fn branch_from_move(&self, mv: Move) -> GameState {
+        debug_assert!(
+            generate_legal_moves(&self.tableau).contains(&mv)
+                || generate_foundation_to_column_moves(&self.tableau).contains(&mv),
+            "branch_from_move: {:?} is not legal after {} moves",
+            mv,
+            self.moves.len()
+        );
+        let mut child = self.clone();
+        child.apply_move(mv);
+        child
+    }
+
+    /// Return the state reached by playing `moves` in order from this one,
+    /// via `branch_from_move` (so each move is legality-checked in debug
+    /// builds). Handy for setting up mid-game positions in tests.
+    pub fn branch_from_moves(&self, moves: &[Move]) -> GameState {
+        let mut state = self.clone();
+        for &mv in moves {
+            state = state.branch_from_move(mv);
+        }
+        state
+    }
+
+    /// The state after only the first `n` moves of this game, with the
+    /// same deck and draw rule; tableau, hash and redeal count are rebuilt
+    /// from those moves (see `from_parts`). Pair with `suffix_moves(n)` to
+    /// bisect a game history.
+    ///
+    /// Panics if `n > self.moves.len()`, or if the deck is unknown (see
+    /// `has_known_deck`).
+    pub fn fork_at(&self, n: usize) -> GameState {
+        assert!(
+            self.has_known_deck(),
+            "cannot fork: state was parsed from a bare tableau and has no known deck"
+        );
+        assert!(
+            n <= self.moves.len(),
+            "fork_at({}) past the end of a {}-move game",
+            n,
+            self.moves.len()
+        );
+        GameState::from_parts(self.initial_deck, self.moves[..n].to_vec(), self.draw_rule())
+    }
+
+    /// The moves played from index `from` onward (`&self.moves[from..]`).
+    ///
+    /// Panics if `from > self.moves.len()`.
+    pub fn suffix_moves(&self, from: usize) -> &[Move] {
+        &self.moves[from..]
+    }
+
+    /// Parse a game saved with `to_compact_str`, or a bare tableau in
+    /// `Tableau::from_compact_str` format.
+    ///
+    /// A saved game has the sections `DECK` (the 52 cards of `initial_deck`
+    /// in `Card::ascii_str` form, comma-separated), optional `D` (draw
+    /// rule, `1` or `3`; draw-3 if absent) and optional `M` (the moves in
+    /// `display::render_compact_notation` form):
+    ///
+    /// ```text
+    /// DECK:AH,2H,...,KD;D:3;M:D C3[2]->C5 W->F
+    /// ```
+    ///
+    /// Every move must be legal where it is played (`FoundationToColumn`
+    /// included), otherwise the result is `ParseError::BadMove`.
+    ///
+    /// A string without a `DECK` section is parsed as a tableau, giving a
+    /// state with no moves whose `initial_deck` is all `Card(0)`: the deck
+    /// that led there is unknown (`has_known_deck` is false). Such a state
+    /// plays forward normally, but anything that replays history from the
+    /// deck refuses it: `pop_move` returns `None`, and `pop_moves`,
+    /// `fork_at` and `recompute_tableau_from_history` panic.
+    pub fn from_compact_str(s: &str) -> Result<GameState, ParseError> {
+        let s = s.trim();
+        let is_saved_game = s
+            .split(';')
+            .any(|section| section.split_once(':').is_some_and(|(key, _)| key.trim() == "DECK"));
+        if !is_saved_game {
+            let tableau = Tableau::from_compact_str(s)?;
+            return Ok(GameState {
+                initial_deck: UNKNOWN_DECK,
+                tableau,
+                moves: Vec::new(),
+                tableau_hash: tableau.compute_hash_with_table(zobrist_table()),
+                redeals_used: 0,
+                stock_cycle_count: 0,
+                termination_reason: None,
+            });
+        }
+
+        let mut deck_value: Option<&str> = None;
+        let mut draw_value: Option<&str> = None;
+        let mut moves_value: Option<&str> = None;
+        for section in s.split(';') {
+            let (key, value) = section
+                .split_once(':')
+                .ok_or_else(|| ParseError::MalformedSection(section.to_string()))?;
+            let slot = match key.trim() {
+                "DECK" => &mut deck_value,
+                "D" => &mut draw_value,
+                "M" => &mut moves_value,
+                other => return Err(ParseError::UnexpectedSection(other.to_string())),
+            };
+            if slot.replace(value.trim()).is_some() {
+                return Err(ParseError::UnexpectedSection(key.trim().to_string()));
+            }
+        }
+
+        let deck_value = deck_value.ok_or_else(|| ParseError::MissingSection("DECK".to_string()))?;
+        let tokens: Vec<&str> = deck_value.split(',').map(str::trim).collect();
+        if tokens.len() != CARDS_PER_DECK as usize {
+            return Err(ParseError::WrongCardCount(tokens.len()));
+        }
+        let mut initial_deck = [Card(0); CARDS_PER_DECK as usize];
+        let mut seen = [false; CARDS_PER_DECK as usize];
+        for (slot, tok) in initial_deck.iter_mut().zip(tokens) {
+            let card = Card::from_short_str(tok).ok_or_else(|| ParseError::BadCard(tok.to_string()))?;
+            if std::mem::replace(&mut seen[card.index() as usize], true) {
+                return Err(ParseError::DuplicateCard(card.ascii_str()));
+            }
+            *slot = card;
+        }
+
+        let draw_rule = match draw_value {
+            None | Some("3") => DrawRule::Draw3,
+            Some("1") => DrawRule::Draw1,
+            Some(other) => return Err(ParseError::BadDrawRule(other.to_string())),
+        };
+
+        let mut tableau = Tableau::deal_from_shuffled_with_rule(initial_deck, draw_rule);
+        let mut moves = Vec::new();
+        for (index, notation) in moves_value.unwrap_or("").split_whitespace().enumerate() {
+            let bad = || ParseError::BadMove {
+                index,
+                notation: notation.to_string(),
+            };
+            let mv = Move::from_notation(notation).map_err(|_| bad())?.with_deal_count_for(&tableau);
+            let legal = generate_legal_moves(&tableau).contains(&mv)
+                || generate_foundation_to_column_moves(&tableau).contains(&mv);
+            if !legal {
+                return Err(bad());
+            }
+            mv.apply(&mut tableau);
+            moves.push(mv);
+        }
+
+        Ok(GameState::from_parts(initial_deck, moves, draw_rule))
+    }
+
+    /// False for a state parsed from a bare tableau by `from_compact_str`,
+    /// whose `initial_deck` is only a placeholder: its history cannot be
+    /// replayed from the deal.
+    pub fn has_known_deck(&self) -> bool {
+        self.initial_deck != UNKNOWN_DECK
+    }
+
+    /// Save this game as one line that `from_compact_str` reads back:
+    /// `initial_deck`, draw rule and moves (the tableau follows from
+    /// those). See `from_compact_str` for the format.
+    ///
+    /// A state built from a bare tableau has no known deck, so for it this
+    /// is the current tableau's `Tableau::to_compact_str` instead.
+    pub fn to_compact_str(&self) -> String {
+        if !self.has_known_deck() {
+            return self.tableau.to_compact_str();
+        }
+        let deck: Vec<String> = self.initial_deck.iter().map(|c| c.ascii_str()).collect();
+        format!(
+            "DECK:{};D:{};M:{}",
+            deck.join(","),
+            self.draw_rule().cards_per_deal(),
+            crate::display::render_compact_notation(&self.moves)
+        )
+    }
+
+    /// Undo the most recently applied move: the inverse of `apply_move`,
+    /// which pushes onto the move stack.
+    ///
+    /// Pops the last move from the move stack and rebuilds the tableau from
+    /// scratch via `recompute_tableau_from_history`, then recomputes the
+    /// hash. This is O(moves) per call; it favours obvious correctness over
+    /// speed. Returns the removed move, or `None` if no moves have been
+    /// applied or the deck is unknown (see `has_known_deck`); the state is
+    /// then left unchanged.
+    pub fn pop_move(&mut self) -> Option<Move> {
+        if !self.has_known_deck() {
+            return None;
+        }
+        let mv = self.moves.pop()?;
+        self.tableau = self.recompute_tableau_from_history();
+        self.tableau_hash = self.tableau.compute_hash_with_table(zobrist_table());
+        self.redeals_used = count_redeals(&self.moves);
+        self.stock_cycle_count = count_stock_cycles(&self.moves);
+        self.assert_consistent();
+        Some(mv)
+    }
+
+    /// Undo the last `n` moves at once.
+    ///
+    /// Truncates the move stack and rebuilds the tableau (and hash) from the
+    /// remaining history once, so it costs the same as a single
+    /// `pop_move` rather than `n` of them. Saturates: if `n` exceeds the
+    /// number of moves applied, the game is reset to its initial deal.
+    /// Returns the removed moves in the order they were applied.
+    ///
+    /// Panics if the deck is unknown (see `has_known_deck`) and `n > 0`.
+    pub fn pop_moves(&mut self, n: usize) -> Vec<Move> {
+        assert!(
+            n == 0 || self.has_known_deck(),
+            "cannot undo: state was parsed from a bare tableau and has no known deck"
+        );
+        let keep = self.moves.len().saturating_sub(n);
+        let undone = self.moves.split_off(keep);
+        if !undone.is_empty() {
+            self.tableau = self.recompute_tableau_from_history();
+            self.tableau_hash = self.tableau.compute_hash_with_table(zobrist_table());
+            self.redeals_used = count_redeals(&self.moves);
+            self.stock_cycle_count = count_stock_cycles(&self.moves);
+            self.assert_consistent();
+        }
+        undone
     }
 
     /// Reconstruct the current tableau from scratch by dealing the initial
@@ -199,14 +793,89 @@ impl GameState {
     ///
     /// This is mainly useful as a consistency/debug helper; normal code
     /// should rely on the cached `tableau` field and `apply_move`.
+    ///
+    /// Panics if the deck is unknown (see `has_known_deck`).
     pub fn recompute_tableau_from_history(&self) -> Tableau {
-        let mut tab = Tableau::deal_from_shuffled(self.initial_deck);
+        assert!(
+            self.has_known_deck(),
+            "cannot replay history: state was parsed from a bare tableau and has no known deck"
+        );
+        let mut tab =
+            Tableau::deal_from_shuffled_with_rule(self.initial_deck, self.tableau.draw_rule);
         for mv in &self.moves {
             mv.apply(&mut tab);
         }
         tab
     }
 
+    /// Panic with a descriptive message if this state is internally
+    /// inconsistent:
+    ///   - the tableau holds all 52 cards
+    ///   - every non-empty column has a face-up card on top
+    ///   - `tableau_hash` equals a full Zobrist recompute
+    ///   - `redeals_used` and `stock_cycle_count` match the `RedealStock`
+    ///     moves in `moves`
+    ///   - replaying `moves` from the deal gives the cached tableau (when
+    ///     the deck is known, see `has_known_deck`)
+    ///
+    /// Debug builds only: in release builds this does nothing. Runs at the
+    /// end of every `pop_move`; `apply_move` runs only the checks that do
+    /// not scan the move history.
+    pub fn assert_consistent(&self) {
+        self.assert_cached_state_consistent();
+        #[cfg(debug_assertions)]
+        {
+            assert_eq!(
+                self.redeals_used,
+                count_redeals(&self.moves),
+                "inconsistent GameState after {} moves: redeals_used out of sync",
+                self.moves.len()
+            );
+            assert_eq!(
+                self.stock_cycle_count,
+                count_stock_cycles(&self.moves),
+                "inconsistent GameState after {} moves: stock_cycle_count out of sync",
+                self.moves.len()
+            );
+            assert!(
+                !self.has_known_deck() || self.recompute_tableau_from_history() == self.tableau,
+                "inconsistent GameState after {} moves: cached tableau differs from replayed history",
+                self.moves.len()
+            );
+        }
+    }
+
+    /// The O(1) checks of `assert_consistent`, without the scans of the
+    /// move history.
+    fn assert_cached_state_consistent(&self) {
+        #[cfg(debug_assertions)]
+        {
+            let total = self.tableau.total_cards();
+            assert_eq!(
+                total, CARDS_PER_DECK,
+                "inconsistent GameState after {} moves: tableau holds {} cards",
+                self.moves.len(),
+                total
+            );
+            for (i, col) in self.tableau.columns.iter().enumerate() {
+                assert!(
+                    col.len == 0 || col.num_face_down < col.len,
+                    "inconsistent GameState after {} moves: column {} has {} cards, all face-down",
+                    self.moves.len(),
+                    i + 1,
+                    col.len
+                );
+            }
+            assert_eq!(
+                self.tableau_hash,
+                self.tableau.compute_hash_with_table(zobrist_table()),
+                "inconsistent GameState after {} moves: tableau_hash out of sync (last move {:?})",
+                self.moves.len(),
+                self.moves.last()
+            );
+        }
+    }
+
     /// Return a copy of the current tableau.
     ///
     /// Because `Tableau` is `Copy` in this project, this returns by value.
@@ -216,6 +885,206 @@ impl GameState {
     }
 }
 
+/// Number of `RedealStock` moves in a move stack, saturating at `u8::MAX`.
+fn count_redeals(moves: &[Move]) -> u8 {
+    let n = moves
+        .iter()
+        .filter(|mv| matches!(mv.kind, MoveKind::RedealStock))
+        .count();
+    u8::try_from(n).unwrap_or(u8::MAX)
+}
+
+/// Number of `RedealStock` moves in a move stack, for `stock_cycle_count`.
+fn count_stock_cycles(moves: &[Move]) -> u32 {
+    let n = moves
+        .iter()
+        .filter(|mv| matches!(mv.kind, MoveKind::RedealStock))
+        .count();
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
+
+/// Serialized form of a `GameState`: only what is needed to rebuild it.
+///
+/// The tableau and its hash are derived data and are recomputed by
+/// replaying `moves` on deserialization; `termination_reason` is search
+/// metadata and is not saved.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GameStateRepr {
+    initial_deck: Vec<Card>,
+    moves: Vec<Move>,
+    draw_rule: DrawRule,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for GameState {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameStateRepr {
+            initial_deck: self.initial_deck.to_vec(),
+            moves: self.moves.clone(),
+            draw_rule: self.draw_rule(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GameState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = GameStateRepr::deserialize(deserializer)?;
+        let n = repr.initial_deck.len();
+        let initial_deck: [Card; CARDS_PER_DECK as usize] =
+            repr.initial_deck.try_into().map_err(|_| {
+                serde::de::Error::custom(format!(
+                    "initial_deck has {} cards, expected {}",
+                    n, CARDS_PER_DECK
+                ))
+            })?;
+        Ok(GameState::from_parts(initial_deck, repr.moves, repr.draw_rule))
+    }
+}
+
+/// On-disk form used by `GameState::export_to_json_file`: cards as short
+/// ASCII strings ("AH") and moves in `Move::to_notation` form, so saved
+/// games stay readable and do not depend on the serde layout of `Move`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GameFile {
+    initial_deck: Vec<String>,
+    moves: Vec<String>,
+    draw_rule: DrawRule,
+}
+
+#[cfg(feature = "serde")]
+impl GameState {
+    /// Save this game (initial deck, draw rule and move history) to a
+    /// pretty-printed JSON file at `path`.
+    pub fn export_to_json_file(&self, path: &Path) -> io::Result<()> {
+        let file = GameFile {
+            initial_deck: self.initial_deck.iter().map(|c| c.ascii_str()).collect(),
+            moves: self.moves.iter().map(Move::to_notation).collect(),
+            draw_rule: self.draw_rule(),
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// Load a game written by `export_to_json_file`.
+    ///
+    /// Every move is checked against the legal moves of the position it is
+    /// played from (including `FoundationToColumn`, which relaxed-rule
+    /// searches may have used) before the game is rebuilt with
+    /// `GameState::from_parts`.
+    pub fn import_from_json_file(path: &Path) -> Result<GameState, LoadError> {
+        let text = fs::read_to_string(path)?;
+        let file: GameFile =
+            serde_json::from_str(&text).map_err(|e| LoadError::ParseError(e.to_string()))?;
+
+        if file.initial_deck.len() != CARDS_PER_DECK as usize {
+            return Err(LoadError::WrongCardCount(file.initial_deck.len()));
+        }
+        let mut initial_deck = [Card(0); CARDS_PER_DECK as usize];
+        let mut seen = [false; CARDS_PER_DECK as usize];
+        for (slot, s) in initial_deck.iter_mut().zip(&file.initial_deck) {
+            let card: Card = s
+                .parse()
+                .map_err(|e| LoadError::ParseError(format!("card '{}': {}", s, e)))?;
+            if seen[card.index() as usize] {
+                return Err(LoadError::ParseError(format!("card '{}' appears more than once", s)));
+            }
+            seen[card.index() as usize] = true;
+            *slot = card;
+        }
+
+        let mut tableau = Tableau::deal_from_shuffled_with_rule(initial_deck, file.draw_rule);
+        let mut moves = Vec::with_capacity(file.moves.len());
+        for (index, notation) in file.moves.iter().enumerate() {
+            let invalid = || LoadError::InvalidMove {
+                index,
+                notation: notation.clone(),
+            };
+            let mv = Move::from_notation(notation)
+                .map_err(|_| invalid())?
+                .with_deal_count_for(&tableau);
+            let legal = generate_legal_moves(&tableau).contains(&mv)
+                || generate_foundation_to_column_moves(&tableau).contains(&mv);
+            if !legal {
+                return Err(invalid());
+            }
+            mv.apply(&mut tableau);
+            moves.push(mv);
+        }
+
+        Ok(GameState::from_parts(initial_deck, moves, file.draw_rule))
+    }
+}
+
+/// `initial_deck` marker for a `GameState` built from a bare tableau by
+/// `GameState::from_compact_str`. No real deck repeats a card, so this
+/// cannot clash with one.
+const UNKNOWN_DECK: [Card; CARDS_PER_DECK as usize] = [Card(0); CARDS_PER_DECK as usize];
+
+/// Why `replay_game` rejected a move list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The move at this (0-based) step is not in `generate_legal_moves`
+    /// for the position reached by the moves before it; `legal` lists the
+    /// moves that were.
+    IllegalMoveAtStep {
+        step: usize,
+        mv: Move,
+        legal: Vec<Move>,
+    },
+    /// Every move was legal, but `assert_win` was set and the final
+    /// position is not a win.
+    ExpectedWin,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::IllegalMoveAtStep { step, mv, legal } => write!(
+                f,
+                "move {} ({}) is not legal there ({} legal moves)",
+                step + 1,
+                mv.to_notation(),
+                legal.len()
+            ),
+            ReplayError::ExpectedWin => write!(f, "moves are legal but do not end in a win"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Deal `initial_deck` under `draw_rule` and play `moves`, checking each
+/// one against `generate_legal_moves` before applying it.
+///
+/// Unlike `GameState::from_parts`, which trusts its move list, this is
+/// meant for lines from outside the solver (files, other tools). With
+/// `assert_win` the final position must also be a win. Legality follows
+/// standard rules, so `FoundationToColumn` moves are rejected.
+pub fn replay_game(
+    initial_deck: [Card; CARDS_PER_DECK as usize],
+    moves: &[Move],
+    draw_rule: DrawRule,
+    assert_win: bool,
+) -> Result<GameState, ReplayError> {
+    let mut game = GameState::new(initial_deck, draw_rule);
+    for (step, &mv) in moves.iter().enumerate() {
+        let mv = mv.with_deal_count_for(&game.tableau);
+        let legal = generate_legal_moves(&game.tableau);
+        if !legal.contains(&mv) {
+            return Err(ReplayError::IllegalMoveAtStep { step, mv, legal });
+        }
+        game.apply_move(mv);
+    }
+    if assert_win && !game.is_win() {
+        return Err(ReplayError::ExpectedWin);
+    }
+    Ok(game)
+}
+
 /// Convenience: build and print a tableau from an already-shuffled deck.
 ///
 /// `deck` must be in the same convention used by `Tableau::deal_from_shuffled`:
@@ -243,3 +1112,613 @@ pub fn layout_from_imported_deck_indices(
 
     layout_from_imported_deck(deck)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use crate::card::shuffled_deck_from_seed;
+    use crate::moves::generate_legal_moves;
+
+    /// Fuzz test: play random legal move sequences on random decks under
+    /// both draw rules, and check after every move that the incrementally
+    /// updated Zobrist hash equals a full recompute, and that replaying the
+    /// move history from scratch lands on the same hash.
+    #[test]
+    fn incremental_zobrist_matches_full_recompute() {
+        let table = zobrist_table();
+        let mut rng: u32 = 0x2545_f491;
+        let mut next = || {
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            rng >> 8
+        };
+
+        let mut moves_checked = 0usize;
+        for seed in 1..=40u32 {
+            for draw_rule in [DrawRule::Draw1, DrawRule::Draw3] {
+                let deck = shuffled_deck_from_seed(seed);
+                let mut game = GameState::new(deck, draw_rule);
+                assert_eq!(game.tableau_hash, table.hash_tableau(&game.tableau));
+
+                for _ in 0..300 {
+                    let moves = generate_legal_moves(&game.tableau);
+                    if moves.is_empty() {
+                        break;
+                    }
+                    let mv = moves[next() as usize % moves.len()];
+                    game.apply_move(mv);
+                    moves_checked += 1;
+
+                    assert_eq!(
+                        game.tableau_hash,
+                        table.hash_tableau(&game.tableau),
+                        "seed {} ({:?}): hash mismatch after {:?}",
+                        seed,
+                        draw_rule,
+                        mv
+                    );
+                }
+
+                let replayed = GameState::from_parts(deck, game.moves.clone(), draw_rule);
+                assert_eq!(replayed.tableau_hash, game.tableau_hash);
+            }
+        }
+
+        println!("Checked incremental Zobrist hash over {} moves", moves_checked);
+        assert!(moves_checked > 1000);
+    }
+
+    /// Random play over many decks; any two positions with the same Zobrist
+    /// (or FNV) hash must be the same tableau.
+    #[test]
+    fn zobrist_hash_has_no_collisions_on_random_positions() {
+        let own = ZobristTable::new_deterministic();
+        let mut rng: u32 = 0x1234_5678;
+        let mut next = || {
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            rng >> 8
+        };
+
+        let mut by_zobrist: HashMap<u64, Tableau> = HashMap::new();
+        let mut by_fnv: HashMap<u64, Tableau> = HashMap::new();
+        let mut positions = 0usize;
+        let mut seed = 0u32;
+        while positions < 10_000 {
+            seed += 1;
+            let mut game = GameState::new(shuffled_deck_from_seed(seed), DrawRule::Draw3);
+            for _ in 0..100 {
+                let h = game.tableau.compute_hash_with_table(&own);
+                assert_eq!(h, game.tableau_hash);
+                let prev = *by_zobrist.entry(h).or_insert(game.tableau);
+                assert_eq!(prev, game.tableau, "Zobrist collision 0x{:016x}", h);
+                let f = game.tableau.compute_hash();
+                let prev = *by_fnv.entry(f).or_insert(game.tableau);
+                assert_eq!(prev, game.tableau, "FNV collision 0x{:016x}", f);
+                positions += 1;
+
+                let moves = generate_legal_moves(&game.tableau);
+                if moves.is_empty() {
+                    break;
+                }
+                game.apply_move(moves[next() as usize % moves.len()]);
+            }
+        }
+        println!(
+            "{} positions over {} decks, {} distinct tableaus",
+            positions,
+            seed,
+            by_zobrist.len()
+        );
+        assert_eq!(by_zobrist.len(), by_fnv.len());
+    }
+
+    /// `apply_move` followed by `pop_move` must restore the exact prior
+    /// hash, all the way back to the initial deal.
+    #[test]
+    fn pop_move_restores_previous_hash() {
+        let deck = shuffled_deck_from_seed(3);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        let mut hashes = vec![game.tableau_hash];
+        let mut applied = Vec::new();
+
+        for _ in 0..40 {
+            let moves = generate_legal_moves(&game.tableau);
+            let Some(&mv) = moves.first() else {
+                break;
+            };
+            game.apply_move(mv);
+            hashes.push(game.tableau_hash);
+            applied.push(mv);
+        }
+        assert!(!applied.is_empty());
+
+        while let Some(expected) = applied.pop() {
+            hashes.pop();
+            let undone = game.pop_move();
+            assert_eq!(undone, Some(expected));
+            assert_eq!(game.tableau_hash, *hashes.last().unwrap());
+        }
+        assert!(game.is_at_initial());
+    }
+
+    #[test]
+    fn redeals_used_tracks_redeal_moves() {
+        let deck = shuffled_deck_from_seed(2);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        assert_eq!(game.redeals_used, 0);
+
+        while !game.tableau.stock.is_empty() {
+            game.apply_move(Move::deal_from_stock(&game.tableau));
+        }
+        game.apply_move(Move { kind: MoveKind::RedealStock });
+        assert_eq!(game.redeals_used, 1);
+
+        let rebuilt = GameState::from_parts(deck, game.moves.clone(), DrawRule::Draw3);
+        assert_eq!(rebuilt.redeals_used, 1);
+
+        assert_eq!(game.pop_move(), Some(Move { kind: MoveKind::RedealStock }));
+        assert_eq!(game.redeals_used, 0);
+    }
+
+    #[test]
+    fn stock_cycle_count_increments_once_per_redeal_and_is_capped() {
+        use crate::moves::MovePolicy;
+        use crate::search::{generate_legal_moves_within_limits, SearchLimits};
+
+        let limits = SearchLimits::builder().max_stock_cycles(Some(1)).build();
+        let deck = shuffled_deck_from_seed(2);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        for cycle in 0..2u32 {
+            assert_eq!(game.stock_cycle_count, cycle);
+            while !game.tableau.stock.is_empty() {
+                game.apply_move(Move::deal_from_stock(&game.tableau));
+                assert_eq!(game.stock_cycle_count, cycle);
+            }
+            let offers_redeal = generate_legal_moves_within_limits(&game, &limits, MovePolicy::default())
+                .iter()
+                .any(|mv| mv.kind == MoveKind::RedealStock);
+            if cycle == 0 {
+                assert!(offers_redeal);
+                game.apply_move(Move { kind: MoveKind::RedealStock });
+            } else {
+                assert!(!offers_redeal, "max_stock_cycles = 1 must block a second redeal");
+            }
+        }
+        assert_eq!(game.stock_cycle_count, 1);
+        assert_eq!(GameState::from_parts(deck, game.moves.clone(), DrawRule::Draw3).stock_cycle_count, 1);
+        game.pop_moves(game.move_count());
+        assert_eq!(game.stock_cycle_count, 0);
+    }
+
+    #[test]
+    fn pop_moves_rewinds_to_fresh_deal() {
+        let deck = shuffled_deck_from_seed(8);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        let fresh = game.clone();
+        let mut rng: u32 = 17;
+        for _ in 0..20 {
+            let moves = generate_legal_moves(&game.tableau);
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            game.apply_move(moves[(rng >> 8) as usize % moves.len()]);
+        }
+        let applied = game.moves.clone();
+
+        // Partial rewind keeps the prefix.
+        let mut partial = game.clone();
+        assert_eq!(partial.pop_moves(5), applied[15..].to_vec());
+        assert_eq!(partial.moves, applied[..15].to_vec());
+        assert_eq!(
+            partial.tableau_hash,
+            GameState::from_parts(deck, applied[..15].to_vec(), DrawRule::Draw3).tableau_hash
+        );
+
+        assert_eq!(game.pop_moves(20), applied);
+        assert!(game.is_at_initial());
+        assert_eq!(game.tableau, fresh.tableau);
+        assert_eq!(game.tableau, Tableau::deal_from_shuffled(deck));
+        assert_eq!(game.tableau_hash, fresh.tableau_hash);
+
+        // Saturating: asking for more than there is resets to the deal.
+        let mut over = partial.clone();
+        assert_eq!(over.pop_moves(100).len(), 15);
+        assert_eq!(over.tableau, fresh.tableau);
+        assert!(over.pop_moves(1).is_empty());
+    }
+
+    #[test]
+    fn branch_from_moves_matches_apply_move_and_leaves_parent() {
+        let deck = shuffled_deck_from_seed(4);
+        let root = GameState::new(deck, DrawRule::Draw3);
+        let mut played = root.clone();
+        for _ in 0..15 {
+            let moves = generate_legal_moves(&played.tableau);
+            let Some(&mv) = moves.last() else {
+                break;
+            };
+            played.apply_move(mv);
+        }
+
+        let branched = root.branch_from_moves(&played.moves);
+        assert_eq!(branched.moves, played.moves);
+        assert_eq!(branched.tableau, played.tableau);
+        assert_eq!(branched.tableau_hash, played.tableau_hash);
+        assert!(root.is_at_initial());
+
+        let mv = generate_legal_moves(&branched.tableau)[0];
+        let child = branched.branch_from_move(mv);
+        assert_eq!(child.moves.len(), branched.moves.len() + 1);
+        assert_eq!(child.moves.last(), Some(&mv));
+        assert_eq!(branched.moves, played.moves);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "not legal")]
+    fn branch_from_move_rejects_illegal_move_in_debug() {
+        let game = GameState::new(shuffled_deck_from_seed(4), DrawRule::Draw3);
+        // Nothing has been dealt yet, so the waste is empty.
+        game.branch_from_move(Move { kind: MoveKind::RedealStock });
+    }
+
+    #[test]
+    fn assert_consistent_accepts_played_states() {
+        let mut game = GameState::new(shuffled_deck_from_seed(6), DrawRule::Draw1);
+        game.assert_consistent();
+        for _ in 0..30 {
+            let mv = generate_legal_moves(&game.tableau)[0];
+            game.apply_move(mv);
+            game.assert_consistent();
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "tableau_hash out of sync")]
+    fn assert_consistent_catches_corrupted_hash() {
+        let mut game = GameState::new(shuffled_deck_from_seed(6), DrawRule::Draw3);
+        game.tableau_hash ^= 1;
+        game.assert_consistent();
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "differs from replayed history")]
+    fn assert_consistent_catches_tableau_not_matching_history() {
+        let mut game = GameState::new(shuffled_deck_from_seed(6), DrawRule::Draw3);
+        // A legal-looking tableau, but not the one `moves` (empty) leads to.
+        game.tableau = Tableau::deal_from_shuffled_with_rule(shuffled_deck_from_seed(7), DrawRule::Draw3);
+        game.tableau_hash = game.tableau.compute_hash_with_table(zobrist_table());
+        game.assert_consistent();
+    }
+
+    #[test]
+    fn pop_move_on_empty_history_returns_none() {
+        let mut game = GameState::new(shuffled_deck_from_seed(5), DrawRule::Draw1);
+        let initial_hash = game.tableau_hash;
+
+        assert_eq!(game.pop_move(), None);
+        assert_eq!(game.tableau_hash, initial_hash);
+        assert!(game.is_at_initial());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn game_state_serde_round_trip_after_20_moves() {
+        let deck = shuffled_deck_from_seed(11);
+        let mut game = GameState::new(deck, DrawRule::Draw1);
+        let mut rng: u32 = 99;
+        for _ in 0..20 {
+            let moves = generate_legal_moves(&game.tableau);
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            game.apply_move(moves[(rng >> 8) as usize % moves.len()]);
+        }
+
+        let json = serde_json::to_string_pretty(&game).unwrap();
+        println!("{}", json);
+        assert!(json.contains("\"initial_deck\""));
+        assert!(!json.contains("tableau_hash"));
+
+        let back: GameState = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.initial_deck, game.initial_deck);
+        assert_eq!(back.moves, game.moves);
+        assert_eq!(back.draw_rule(), DrawRule::Draw1);
+        assert_eq!(back.tableau_hash, game.tableau_hash);
+        assert_eq!(back.tableau.foundations, game.tableau.foundations);
+        assert!(back.tableau.waste.iter().eq(game.tableau.waste.iter()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tableau_serde_round_trip_keeps_active_cards_only() {
+        let game = GameState::new(shuffled_deck_from_seed(12), DrawRule::Draw3);
+        let json = serde_json::to_string(&game.tableau).unwrap();
+        let back: Tableau = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(back.compute_hash_with_table(zobrist_table()), game.tableau_hash);
+        for (a, b) in back.columns.iter().zip(game.tableau.columns.iter()) {
+            assert!(a.iter_all().eq(b.iter_all()));
+            assert_eq!(a.num_face_down, b.num_face_down);
+        }
+
+        // Deck of the wrong size is rejected.
+        let bad = r#"{"initial_deck":["AH"],"moves":[],"draw_rule":"Draw3"}"#;
+        assert!(serde_json::from_str::<GameState>(bad).is_err());
+    }
+
+    /// Dealing three cards and moving them back via a full stock cycle must
+    /// restore the original hash; a single deal must change it.
+    #[test]
+    fn zobrist_hash_tracks_stock_cycle() {
+        let deck = shuffled_deck_from_seed(7);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        let initial_hash = game.tableau_hash;
+
+        game.apply_move(Move::deal_from_stock(&game.tableau));
+        assert_ne!(game.tableau_hash, initial_hash);
+
+        while !game.tableau.stock.is_empty() {
+            game.apply_move(Move::deal_from_stock(&game.tableau));
+        }
+        game.apply_move(Move {
+            kind: MoveKind::RedealStock,
+        });
+        assert_eq!(game.tableau_hash, initial_hash);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn game_state_json_file_round_trip_after_30_moves() {
+        let deck = shuffled_deck_from_seed(21);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        let mut rng: u32 = 7;
+        for _ in 0..30 {
+            let moves = generate_legal_moves(&game.tableau);
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            game.apply_move(moves[(rng >> 8) as usize % moves.len()]);
+        }
+
+        let path = std::env::temp_dir().join(format!("klondike_game_{}.json", std::process::id()));
+        game.export_to_json_file(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        println!("{}", text);
+        assert!(text.contains(&format!("\"{}\"", deck[0].ascii_str())));
+        assert!(text.contains(&format!("\"{}\"", game.moves[0].to_notation())));
+
+        let back = GameState::import_from_json_file(&path).unwrap();
+        assert_eq!(back.move_count(), 30);
+        assert_eq!(back.initial_deck, game.initial_deck);
+        assert_eq!(back.moves, game.moves);
+        assert_eq!(back.tableau_hash, game.tableau_hash);
+
+        // Each kind of bad file is reported as such.
+        let bad_move = text.replacen(&format!("\"{}\"", game.moves[0].to_notation()), "\"C9->C1\"", 1);
+        std::fs::write(&path, bad_move).unwrap();
+        assert!(matches!(
+            GameState::import_from_json_file(&path),
+            Err(LoadError::InvalidMove { index: 0, .. })
+        ));
+        std::fs::write(&path, r#"{"initial_deck":["AH"],"moves":[],"draw_rule":"Draw3"}"#).unwrap();
+        assert!(matches!(GameState::import_from_json_file(&path), Err(LoadError::WrongCardCount(1))));
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(GameState::import_from_json_file(&path), Err(LoadError::ParseError(_))));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(GameState::import_from_json_file(&path), Err(LoadError::IoError(_))));
+    }
+
+    #[test]
+    fn replay_game_accepts_a_win_and_pinpoints_a_corrupted_move() {
+        use crate::search::{solve_single_deck_greedy, SearchConfig, SearchLimits};
+
+        // Greedy play happens to win this deal, and is far cheaper than DFS.
+        let deck = shuffled_deck_from_seed(143);
+        let cfg = SearchConfig::builder()
+            .limits(SearchLimits::builder().max_nodes(10_000).build())
+            .build();
+        let line = solve_single_deck_greedy(deck, &cfg)
+            .winning_line
+            .expect("greedy wins seed 143");
+
+        let game = replay_game(deck, &line, cfg.draw_rule, true).unwrap();
+        assert!(game.tableau.is_win());
+        assert_eq!(game.moves, line);
+
+        // Not a win yet, which is only an error when a win is asserted.
+        let partial = &line[..line.len() - 1];
+        assert!(replay_game(deck, partial, cfg.draw_rule, false).is_ok());
+        assert_eq!(
+            replay_game(deck, partial, cfg.draw_rule, true).unwrap_err(),
+            ReplayError::ExpectedWin
+        );
+
+        // Replace one move with something illegal at that point.
+        let step = line.len() / 2;
+        let before = GameState::from_parts(deck, line[..step].to_vec(), cfg.draw_rule);
+        let legal = generate_legal_moves(&before.tableau);
+        let bad = [
+            Move { kind: MoveKind::RedealStock },
+            Move::deal_from_stock(&before.tableau),
+            Move { kind: MoveKind::WasteToFoundation },
+        ]
+        .into_iter()
+        .find(|mv| !legal.contains(mv))
+        .expect("some stock or waste move is illegal");
+        let mut corrupted = line.clone();
+        corrupted[step] = bad;
+
+        let err = replay_game(deck, &corrupted, cfg.draw_rule, true).unwrap_err();
+        println!("{}", err);
+        assert_eq!(err, ReplayError::IllegalMoveAtStep { step, mv: bad, legal });
+    }
+
+    #[test]
+    fn game_state_convenience_accessors() {
+        let deck = shuffled_deck_from_seed(143);
+        let mut game = GameState::new(deck, DrawRule::Draw3);
+        assert!(!game.is_win());
+        assert_eq!(game.foundation_progress(), [0; NUM_FOUNDATIONS]);
+        assert_eq!(game.move_at(0), None);
+
+        let first = generate_legal_moves(&game.tableau)[0];
+        game.apply_move(first);
+        assert_eq!(game.move_at(0), Some(&first));
+        assert_eq!(game.move_at(1), None);
+
+        // Greedy play wins seed 143 (see the replay_game test).
+        let cfg = crate::search::SearchConfig::builder()
+            .limits(crate::search::SearchLimits::builder().max_nodes(10_000).build())
+            .build();
+        let line = crate::search::solve_single_deck_greedy(deck, &cfg).winning_line.unwrap();
+        let won = GameState::from_parts(deck, line.clone(), DrawRule::Draw3);
+        assert!(won.is_win());
+        assert_eq!(won.foundation_progress(), [13; NUM_FOUNDATIONS]);
+        assert_eq!(won.move_at(line.len() - 1), line.last());
+    }
+
+    #[test]
+    fn fork_at_rebuilds_a_prefix_of_the_game() {
+        let deck = shuffled_deck_from_seed(6);
+        let mut game = GameState::new(deck, DrawRule::Draw1);
+        for _ in 0..40 {
+            let mv = generate_legal_moves(&game.tableau)[0];
+            game.apply_move(mv);
+        }
+
+        let fresh = GameState::new(deck, DrawRule::Draw1);
+        let start = game.fork_at(0);
+        assert_eq!(start.tableau, fresh.tableau);
+        assert_eq!(start.tableau_hash, fresh.tableau_hash);
+        assert!(start.moves.is_empty());
+
+        let whole = game.fork_at(game.moves.len());
+        assert_eq!(whole.moves, game.moves);
+        assert_eq!(whole.tableau, game.tableau);
+        assert_eq!(whole.tableau_hash, game.tableau_hash);
+        assert_eq!(whole.redeals_used, game.redeals_used);
+
+        for n in [1, 17, 30] {
+            let fork = game.fork_at(n);
+            assert_eq!(fork.moves.len(), n);
+            fork.assert_consistent();
+            // Replaying the suffix gets back to the full game.
+            let rejoined = fork.branch_from_moves(game.suffix_moves(n));
+            assert_eq!(rejoined.tableau, game.tableau);
+        }
+        assert!(game.suffix_moves(game.moves.len()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end")]
+    fn fork_at_past_the_end_panics() {
+        GameState::new(shuffled_deck_from_seed(6), DrawRule::Draw1).fork_at(1);
+    }
+
+    #[test]
+    fn compact_str_round_trips_a_game_and_a_bare_tableau() {
+        let deck = shuffled_deck_from_seed(9);
+        let mut game = GameState::new(deck, DrawRule::Draw1);
+        for _ in 0..25 {
+            let mv = generate_legal_moves(&game.tableau)[0];
+            game.apply_move(mv);
+        }
+
+        let saved = game.to_compact_str();
+        println!("{}", saved);
+        assert!(saved.starts_with("DECK:") && saved.contains(";D:1;M:"));
+        let back = GameState::from_compact_str(&saved).unwrap();
+        assert_eq!(back.initial_deck, game.initial_deck);
+        assert_eq!(back.moves, game.moves);
+        assert_eq!(back.tableau, game.tableau);
+        assert_eq!(back.tableau_hash, game.tableau_hash);
+        assert_eq!(back.to_compact_str(), saved);
+
+        // A fresh game has an empty move section.
+        let fresh = GameState::new(deck, DrawRule::Draw3);
+        assert!(fresh.to_compact_str().ends_with(";D:3;M:"));
+        assert_eq!(GameState::from_compact_str(&fresh.to_compact_str()).unwrap().tableau, fresh.tableau);
+
+        // A bare tableau: no moves, unknown deck, hash of that tableau.
+        let from_tab = GameState::from_compact_str(&game.tableau.to_compact_str()).unwrap();
+        assert!(from_tab.moves.is_empty());
+        assert_eq!(from_tab.initial_deck, UNKNOWN_DECK);
+        assert_eq!(from_tab.tableau, game.tableau);
+        assert_eq!(from_tab.tableau_hash, game.tableau_hash);
+        assert_eq!(from_tab.to_compact_str(), game.tableau.to_compact_str());
+        assert!(game.has_known_deck() && !from_tab.has_known_deck());
+    }
+
+    /// A bare-tableau state plays forward, but will not replay its history
+    /// from the placeholder deck.
+    #[test]
+    fn bare_tableau_state_refuses_to_replay_history() {
+        let tab = Tableau::deal_from_shuffled(shuffled_deck_from_seed(9));
+        let mut state = GameState::from_compact_str(&tab.to_compact_str()).unwrap();
+        let mv = generate_legal_moves(&state.tableau)[0];
+        state.apply_move(mv);
+        let before = state.clone();
+        assert_eq!(state.pop_move(), None);
+        assert_eq!(state.moves, before.moves);
+        assert_eq!(state.tableau, before.tableau);
+        assert!(state.pop_moves(0).is_empty());
+        let forked = std::panic::catch_unwind(|| before.fork_at(0));
+        assert!(forked.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "no known deck")]
+    fn bare_tableau_state_pop_moves_panics() {
+        let tab = Tableau::deal_from_shuffled(shuffled_deck_from_seed(9));
+        let mut state = GameState::from_compact_str(&tab.to_compact_str()).unwrap();
+        state.apply_move(generate_legal_moves(&state.tableau)[0]);
+        state.pop_moves(1);
+    }
+
+    #[test]
+    fn compact_str_rejects_bad_games() {
+        let fresh = GameState::new(shuffled_deck_from_seed(9), DrawRule::Draw3).to_compact_str();
+        // Flipping a column whose top card is already face-up is never legal.
+        assert_eq!(
+            GameState::from_compact_str(&format!("{}D FC7", fresh)).unwrap_err(),
+            ParseError::BadMove { index: 1, notation: "FC7".to_string() }
+        );
+        assert!(matches!(
+            GameState::from_compact_str(&format!("{}D ??", fresh)),
+            Err(ParseError::BadMove { index: 1, .. })
+        ));
+        assert_eq!(
+            GameState::from_compact_str("DECK:AH,2H;M:").unwrap_err(),
+            ParseError::WrongCardCount(2)
+        );
+        assert_eq!(
+            GameState::from_compact_str(&fresh.replace(";D:3", ";D:2")).unwrap_err(),
+            ParseError::BadDrawRule("2".to_string())
+        );
+        assert_eq!(
+            GameState::from_compact_str(&format!("{};X:1", fresh)).unwrap_err(),
+            ParseError::UnexpectedSection("X".to_string())
+        );
+    }
+
+    #[test]
+    fn peek_last_move_and_move_stack_track_applied_moves() {
+        let mut game = GameState::new(shuffled_deck_from_seed(3), DrawRule::Draw3);
+        assert_eq!(game.peek_last_move(), None);
+        assert!(game.move_stack().is_empty());
+
+        let mut applied = Vec::new();
+        for _ in 0..5 {
+            let mv = generate_legal_moves(&game.tableau)[0];
+            game.apply_move(mv);
+            applied.push(mv);
+            assert_eq!(game.peek_last_move(), Some(&mv));
+        }
+        assert_eq!(game.move_stack(), &applied[..]);
+
+        // Peeking leaves the game alone; popping returns the peeked move.
+        let peeked = *game.peek_last_move().unwrap();
+        assert_eq!(game.move_stack().len(), 5);
+        assert_eq!(game.pop_move(), Some(peeked));
+        assert_eq!(game.move_stack(), &applied[..4]);
+        assert_eq!(game.peek_last_move(), applied.get(3));
+    }
+}
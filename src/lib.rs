@@ -7,14 +7,13 @@ pub mod stats;
 pub mod game;
 pub mod canonical_decks;
 pub mod pysol_decks;
+pub mod parse;
 
 use std::env;
 
 use crate::card::CARDS_PER_DECK;
 use crate::display::{print_tableau, print_playing_edge, print_full_piles_debug};
-use crate::game::GameState;
-
-#[allow(dead_code)]
+use crate::search::SearchStrategy;
 
 #[allow(dead_code)]
 fn demo_imported_pysol_deck(deck: [crate::card::Card; CARDS_PER_DECK as usize], label: &str) {
@@ -41,11 +40,129 @@ fn demo_imported_pysol_deck(deck: [crate::card::Card; CARDS_PER_DECK as usize],
     println!("Flattened deck from tableau: [{}]", flat.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "));
 }
 
+/// Parse a `--range-seeds` / `--pysol-range` value of the form
+/// `START:END` (inclusive, `START <= END`).
+fn parse_seed_range<T>(s: &str) -> Result<(T, T), String>
+where
+    T: std::str::FromStr + PartialOrd + std::fmt::Display,
+{
+    let (start, end) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected START:END, got '{}'", s))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<T>()
+            .map_err(|_| format!("could not parse seed '{}' in '{}'", v, s))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start > end {
+        return Err(format!("start {} is after end {}", start, end));
+    }
+    Ok((start, end))
+}
+
+/// Solve `card::shuffled_deck_from_seed(seed)` for every seed in
+/// `start..=end`, printing one terse line per seed
+/// (`GameOutcome::terse_summary`; nothing with `DetailLevel::Silent`), and
+/// return the aggregate stats.
+fn solve_seed_range(start: u32, end: u32, cfg: &search::SearchConfig) -> stats::Stats {
+    let mut range_stats = stats::Stats::default();
+    for seed in start..=end {
+        let deck = card::shuffled_deck_from_seed(seed);
+        let outcome = cfg.strategy.solve(deck, cfg);
+
+        #[cfg(debug_assertions)]
+        if let Some(line) = outcome.winning_line.as_ref()
+            && let Err(e) = search::validate_winning_line(&deck, line, cfg.draw_rule)
+        {
+            panic!("reported win for seed {} failed validation: {}", seed, e);
+        }
+
+        if cfg.detail != search::DetailLevel::Silent {
+            println!("seed {}: {}", seed, outcome.terse_summary());
+        }
+        range_stats.record_outcome(&outcome);
+    }
+    range_stats
+}
+
+/// The deck for `--seed`: seeds that fit in a `u32` keep the original
+/// `card::shuffled_deck_from_seed` decks (so existing seed numbers still
+/// name the same games), larger ones use `card::shuffled_deck_from_seed_u64`.
+fn deck_for_seed(seed: u64) -> [card::Card; CARDS_PER_DECK as usize] {
+    match u32::try_from(seed) {
+        Ok(small) => card::shuffled_deck_from_seed(small),
+        Err(_) => card::shuffled_deck_from_seed_u64(seed),
+    }
+}
+
+/// The result block printed for a single `--seed` deck: the outcome's
+/// search statistics, one per line, or just `seed N: <terse summary>` with
+/// `DetailLevel::Silent`.
+fn seed_report(seed: u64, outcome: &search::GameOutcome, detail: search::DetailLevel) -> String {
+    if detail == search::DetailLevel::Silent {
+        return format!("seed {}: {}\n", seed, outcome.terse_summary());
+    }
+    let mut out = String::new();
+    out.push_str(&format!("Deck seed: {}\n", seed));
+    out.push_str(&format!("Nodes visited: {}\n", outcome.nodes_visited));
+    out.push_str(&format!("Win? {}\n", outcome.is_win));
+    out.push_str(&format!("Termination reason: {:?}\n", outcome.termination));
+    out.push_str(&format!("Max branch depth (moves): {}\n", outcome.max_branch_depth));
+    out.push_str(&format!("Max shelved states: {}\n", outcome.max_shelved));
+    out.push_str(&format!("Dead-end branches: {}\n", outcome.dead_end_branches));
+    out.push_str(&format!("Loop-pruned branches: {}\n", outcome.loop_pruned_branches));
+    out.push_str(&format!(
+        "Solve time: {} ms ({:.0} nodes/s)\n",
+        outcome.solve_duration_ms, outcome.nodes_per_second
+    ));
+    if let Some(line) = outcome.winning_line.as_ref().filter(|_| outcome.is_win) {
+        out.push_str(&format!("Winning line length: {}\n", line.len()));
+    }
+    out
+}
+
+/// The `--pysol-stats` block: deck count, wins, losses, win rate, mean
+/// winning line length, mean nodes visited and the label of the deck with
+/// the longest winning line. Every line ends with a newline.
+fn pysol_stats_report(batch: &search::BatchResult) -> String {
+    let stats = &batch.stats;
+    let longest = batch
+        .outcomes
+        .iter()
+        .filter(|(_, outcome)| outcome.is_win)
+        .filter_map(|(spec, outcome)| outcome.winning_line.as_ref().map(|line| (spec, line.len())))
+        .max_by_key(|&(_, len)| len);
+
+    let mut out = String::new();
+    out.push_str("=== PySol aggregate stats ===\n");
+    out.push_str(&format!("Decks run: {}\n", stats.games_played));
+    out.push_str(&format!("Wins: {}\n", stats.games_won));
+    out.push_str(&format!("Losses: {}\n", stats.games_lost));
+    out.push_str(&format!("Win rate: {:.1}%\n", stats.win_percentage()));
+    out.push_str(&format!("Mean winning line length: {:.1} moves\n", stats.mean_winning_line_length()));
+    out.push_str(&format!("Mean nodes visited: {:.0}\n", stats.mean_nodes_visited()));
+    match longest {
+        Some((spec, len)) => out.push_str(&format!("Longest winning line: {} ({} moves)\n", spec.label, len)),
+        None => out.push_str("Longest winning line: (no wins)\n"),
+    }
+    out
+}
+
 /// Program entry point.
 ///
 /// Supported arguments:
-///   * `--trace`                     → enable per-node DFS tracing
-///   * `--seed=<u32>`                → choose a pseudo-random deck (non-PySol)
+///   * `--verbose=<0|1|2|3>`         → 0 = final result only, 1 = summary (default), 2 = + per-node move counts, 3 = full DFS trace
+///   * `--trace`                     → alias for --verbose=3
+///   * `--seed=<u64>`                → choose a pseudo-random deck (non-PySol); seeds above u32::MAX use the 64-bit shuffle
+///   * `--range-seeds=<START:END>`   → solve seeds START..=END, one terse line per seed, then totals
+///   * `--draw=<1|3>`                → draw-1 or draw-3 stock rule (default 3)
+///   * `--color` / `--no-color`      → ANSI-colored card output (default off)
+///   * `--waste-peek=<N>`            → show the top N waste cards in printed tableaus (default 3)
+///   * `--row-indices`               → number the rows of printed column grids
+///   * `--parallel`                  → solve PySol decks on a rayon thread pool (only with --verbose=0 or 1)
+///   * `--strategy=<dfs|bfs|greedy|idastar|beam>` → search strategy (default dfs)
+///   * `--output=<text|html>`        → `html` prints the initial deal as an HTML table and exits (default text)
 ///
 /// PySol deck ingestion (decks are integer lists from `dump_pysolfc_deal.py`):
 ///   * `--pysol-deck=<LIST>`         → provide one deck list (repeatable)
@@ -55,16 +172,26 @@ fn demo_imported_pysol_deck(deck: [crate::card::Card; CARDS_PER_DECK as usize],
 /// PySol seed ingestion (pure-Rust reproduction of PySolFC + pysol_cards shuffles):
 ///   * `--pysol-seed=<SEED>`         → generate a deck from a PySolFC game number / seed (repeatable)
 ///   * `--pysol-seed-file=<PATH>`    → load one seed per line from a text file (blank lines and comments allowed)
+///   * `--pysol-seed-file-range=<START:END>` → only read lines START..=END (1-based) of each seed file
+///   * `--pysol-seed-file-skip-errors` → do not warn about seed file lines that fail to parse
+///   * `--pysol-range=<START:END>`   → generate decks for every game number in START..=END
 ///
 /// Running subsets:
 ///   * `--pysol-only=<N>`            → run only the Nth loaded PySol deck (1-based)
 ///   * `--pysol-label=<TEXT>`        → run only decks whose label contains TEXT
 ///   * `--pysol-label` also applies to seeds (labels are "seed:<...>")
+///   * `--pysol-dedup`               → drop repeated deck permutations (first occurrence wins)
+///   * `--pysol-stats`               → after the batch, print an aggregate block (wins, losses,
+///     win rate, mean line length and nodes, longest winning line); printed in every output mode
 ///
 /// Output:
 ///   * For PySol decks: always prints per-deck summary/stats. On wins, printing the full winning move
 ///     sequence is controlled by `--pysol-moves` / `--pysol-output=moves` (default is summary-only).
+///     After the batch, prints aggregate win/loss counts plus histograms of winning line length
+///     and max branch depth.
 ///   * For non-PySol decks: prints summary stats; use `--print-winning-moves` to print a winning line.
+///   * Debug builds replay every reported win with `search::validate_winning_line` and panic if
+///     it does not hold up.
 ///
 /// Example (single deck inline):
 ///   cargo run --release -- --pysol-deck="[51, 32, 3, ...]" 
@@ -75,12 +202,12 @@ fn demo_imported_pysol_deck(deck: [crate::card::Card; CARDS_PER_DECK as usize],
 /// Example (run only the 3rd deck from file):
 ///   cargo run --release -- --pysol-deck-file=pysol_decks.txt --pysol-only=3
 pub fn run() {
-    println!("klondike_chat: Klondike solver skeleton starting up");
-    println!();
-
     // Defaults: summary-only search with a fixed seed.
     let mut detail = search::DetailLevel::Summary;
-    let mut seed: u32 = 1;
+    let mut seed: u64 = 1;
+    let mut seed_range: Option<(u32, u32)> = None;
+    let mut draw_rule = tableau::DrawRule::Draw3;
+    let mut strategy = search::SearchStrategyKind::Dfs;
 
     // Optional: print the full winning move sequence (even in Summary mode).
     let mut print_winning_moves: bool = false;
@@ -96,9 +223,15 @@ pub fn run() {
     // PySol output defaults to summary (wins do not dump the whole move list unless requested).
     let mut pysol_output_mode: PysolOutputMode = PysolOutputMode::Summary;
 
+    // Optional: print the initial deal as an HTML table and exit.
+    let mut html_output: bool = false;
+
     // Optional: show the tableau for the first loaded PySol deck and exit.
     let mut demo_pysol: bool = false;
 
+    // Solve PySol batches in parallel instead of one deck at a time.
+    let mut parallel: bool = false;
+
     // PySol deck sources.
     let mut pysol_deck_literals: Vec<String> = Vec::new();
     let mut pysol_deck_files: Vec<String> = Vec::new();
@@ -106,23 +239,78 @@ pub fn run() {
     // PySol seed sources.
     let mut pysol_seed_literals: Vec<String> = Vec::new();
     let mut pysol_seed_files: Vec<String> = Vec::new();
+    let mut pysol_seed_ranges: Vec<(u64, u64)> = Vec::new();
+    let mut pysol_seed_file_lines: Option<(usize, usize)> = None;
+    let mut pysol_seed_file_skip_errors: bool = false;
 
     // PySol selection.
     let mut pysol_only_index: Option<usize> = None; // 1-based
     let mut pysol_label_filter: Option<String> = None;
+    let mut pysol_dedup: bool = false;
+    let mut pysol_stats: bool = false;
 
     // Very small hand-rolled argument parser.
     for arg in env::args().skip(1) {
         if arg == "--trace" {
             detail = search::DetailLevel::Trace;
+        } else if let Some(rest) = arg.strip_prefix("--verbose=") {
+            match rest.parse::<u8>().ok().and_then(search::DetailLevel::from_verbosity) {
+                Some(level) => detail = level,
+                None => eprintln!("Warning: --verbose expects 0, 1, 2 or 3, got '{}'", rest),
+            }
         } else if let Some(rest) = arg.strip_prefix("--seed=") {
-            match rest.parse::<u32>() {
+            match rest.parse::<u64>() {
                 Ok(v) => seed = v,
                 Err(_) => eprintln!(
                     "Warning: could not parse seed from '{}'; using default {}",
                     rest, seed
                 ),
             }
+        } else if let Some(rest) = arg.strip_prefix("--range-seeds=") {
+            match parse_seed_range(rest) {
+                Ok(range) => seed_range = Some(range),
+                Err(e) => eprintln!("Warning: --range-seeds: {}", e),
+            }
+        } else if let Some(rest) = arg.strip_prefix("--draw=") {
+            match rest {
+                "1" => draw_rule = tableau::DrawRule::Draw1,
+                "3" => draw_rule = tableau::DrawRule::Draw3,
+                _ => eprintln!("Warning: --draw expects '1' or '3', got '{}'", rest),
+            }
+        } else if let Some(rest) = arg.strip_prefix("--strategy=") {
+            match rest {
+                "dfs" => strategy = search::SearchStrategyKind::Dfs,
+                "bfs" => strategy = search::SearchStrategyKind::Bfs,
+                "greedy" => strategy = search::SearchStrategyKind::Greedy,
+                "idastar" => strategy = search::SearchStrategyKind::IdaStar,
+                "beam" => strategy = search::SearchStrategyKind::Beam,
+                _ => eprintln!(
+                    "Warning: --strategy expects 'dfs', 'bfs', 'greedy', 'idastar' or 'beam', got '{}'",
+                    rest
+                ),
+            }
+        } else if let Some(rest) = arg.strip_prefix("--output=") {
+            match rest {
+                "text" => html_output = false,
+                "html" => html_output = true,
+                _ => eprintln!("Warning: --output expects 'text' or 'html', got '{}'", rest),
+            }
+        } else if arg == "--color" {
+            display::set_color_mode(display::ColorMode::Color);
+        } else if arg == "--no-color" {
+            display::set_color_mode(display::ColorMode::NoColor);
+        } else if arg == "--row-indices" {
+            display::set_display_config(display::DisplayConfig {
+                show_row_indices: true,
+                ..display::display_config()
+            });
+        } else if let Some(rest) = arg.strip_prefix("--waste-peek=") {
+            match rest.parse::<u8>() {
+                Ok(n) => display::set_waste_peek(n),
+                Err(_) => eprintln!("Warning: could not parse --waste-peek value '{}'", rest),
+            }
+        } else if arg == "--parallel" {
+            parallel = true;
         } else if arg == "--print-winning-moves" || arg == "--print-moves" {
             print_winning_moves = true;
         } else if arg == "--pysol-summary" {
@@ -153,10 +341,26 @@ pub fn run() {
             }
         } else if let Some(rest) = arg.strip_prefix("--pysol-label=") {
             pysol_label_filter = Some(rest.to_string());
+        } else if arg == "--pysol-dedup" {
+            pysol_dedup = true;
+        } else if arg == "--pysol-stats" {
+            pysol_stats = true;
         } else if let Some(rest) = arg.strip_prefix("--pysol-seed=") {
             pysol_seed_literals.push(rest.to_string());
         } else if let Some(rest) = arg.strip_prefix("--pysol-seed-file=") {
             pysol_seed_files.push(rest.to_string());
+        } else if let Some(rest) = arg.strip_prefix("--pysol-seed-file-range=") {
+            match parse_seed_range::<usize>(rest) {
+                Ok(range) => pysol_seed_file_lines = Some(range),
+                Err(e) => eprintln!("Warning: --pysol-seed-file-range: {}", e),
+            }
+        } else if arg == "--pysol-seed-file-skip-errors" {
+            pysol_seed_file_skip_errors = true;
+        } else if let Some(rest) = arg.strip_prefix("--pysol-range=") {
+            match parse_seed_range::<u64>(rest) {
+                Ok(range) => pysol_seed_ranges.push(range),
+                Err(e) => eprintln!("Warning: --pysol-range: {}", e),
+            }
         } else {
             eprintln!(
                 "Warning: unrecognized argument '{}'; try --help in the README/comments",
@@ -165,6 +369,13 @@ pub fn run() {
         }
     }
 
+    // --verbose=0: print nothing but the final result line.
+    let silent = detail == search::DetailLevel::Silent;
+    if !silent {
+        println!("klondike_chat: Klondike solver skeleton starting up");
+        println!();
+    }
+
     // --- Load PySol decks (if any were provided) ---
     let mut pysol_decks: Vec<pysol_decks::DeckSpec> = Vec::new();
 
@@ -195,12 +406,28 @@ pub fn run() {
     }
     for file in pysol_seed_files {
         let path = std::path::Path::new(&file);
-        match pysol_decks::load_seeds_from_file(path) {
-            Ok(mut specs) => pysol_decks.append(&mut specs),
-            Err(e) => eprintln!("Warning: {}", e),
+        let line_range = pysol_seed_file_lines.map(|(start, end)| start..=end);
+        let (mut specs, errors) = pysol_decks::load_seeds_from_file_tolerant(path, line_range);
+        pysol_decks.append(&mut specs);
+        if !pysol_seed_file_skip_errors {
+            for e in errors {
+                eprintln!("Warning: {}", e);
+            }
+        }
+    }
+    for (start, end) in pysol_seed_ranges {
+        for result in pysol_decks::generate_seed_range_parallel(start, end) {
+            match result {
+                Ok(spec) => pysol_decks.push(spec),
+                Err(e) => eprintln!("Warning: --pysol-range: {}", e),
+            }
         }
     }
 
+    if pysol_dedup {
+        pysol_decks::dedup_decks(&mut pysol_decks);
+    }
+
     // Apply selection filters if present.
     if let Some(label_substr) = pysol_label_filter.clone() {
         pysol_decks.retain(|d| d.label.contains(&label_substr));
@@ -230,21 +457,77 @@ pub fn run() {
         return;
     }
 
-    let cfg = search::SearchConfig {
-        limits: search::SearchLimits::default(),
-        detail,
-    };
+    // HTML output: render the initial deal of the first PySol deck (or the
+    // `--seed` deck) and exit without solving.
+    if html_output {
+        let deck = match pysol_decks.first() {
+            Some(spec) => spec.deck,
+            None => deck_for_seed(seed),
+        };
+        let tab = tableau::Tableau::deal_from_shuffled_with_rule(deck, draw_rule);
+        println!("{}", display::render_tableau_html(&tab));
+        return;
+    }
+
+    let cfg = search::SearchConfig::builder()
+        .detail(detail)
+        .draw_rule(draw_rule)
+        .strategy(strategy)
+        .build();
 
     // --- If any PySol decks were provided, run them (one or all) ---
     if !pysol_decks.is_empty() {
-        println!("Loaded {} PySol deck(s).", pysol_decks.len());
-        println!();
+        if !silent {
+            println!("Loaded {} PySol deck(s).", pysol_decks.len());
+            println!();
+        }
 
-        for (i, spec) in pysol_decks.iter().enumerate() {
-            println!("=== PySol deck {} / {} (label: {}) ===", i + 1, pysol_decks.len(), spec.label);
+        let batch_start = std::time::Instant::now();
 
-            let outcome = search::solve_single_deck_with_config(spec.deck, &cfg);
+        let batch = if parallel {
+            match search::solve_batch_parallel(&pysol_decks, &cfg) {
+                Ok(outcomes) => search::BatchResult::new(
+                    &pysol_decks,
+                    outcomes,
+                    batch_start.elapsed().as_millis() as u64,
+                ),
+                Err(e) => {
+                    eprintln!("Error: --parallel: {}", e);
+                    std::process::exit(2);
+                }
+            }
+        } else {
+            // Per-node output (--verbose=2 or 3) is printed while a deck is
+            // being solved, so name the deck first; otherwise just show
+            // progress on stderr.
+            let progress = |done: usize, total: usize| {
+                if detail >= search::DetailLevel::MoveCounts {
+                    if let Some(spec) = pysol_decks.get(done) {
+                        println!("=== Solving PySol deck {} / {} (label: {}) ===", done + 1, total, spec.label);
+                    }
+                } else if !silent {
+                    eprint!("\rSolved {} / {} deck(s)", done, total);
+                    if done == total {
+                        eprintln!();
+                    }
+                }
+            };
+            search::solve_batch_sequential(&pysol_decks, &cfg, Some(&progress))
+        };
+
+        for (i, (spec, outcome)) in batch.outcomes.iter().enumerate() {
+            #[cfg(debug_assertions)]
+            if let Some(line) = outcome.winning_line.as_ref()
+                && let Err(e) = search::validate_winning_line(&spec.deck, line, cfg.draw_rule)
+            {
+                panic!("reported win for '{}' failed validation: {}", spec.label, e);
+            }
+
+            if silent {
+                continue;
+            }
 
+            println!("=== PySol deck {} / {} (label: {}) ===", i + 1, batch.outcomes.len(), spec.label);
             // Always print a per-deck summary. (This is the ""skeleton"" solver, so a win can
             // still include a very long line; printing it is optional.)
             println!("Nodes visited: {}", outcome.nodes_visited);
@@ -254,20 +537,17 @@ pub fn run() {
             println!("Max shelved states: {}", outcome.max_shelved);
             println!("Dead-end branches: {}", outcome.dead_end_branches);
             println!("Loop-pruned branches: {}", outcome.loop_pruned_branches);
+            println!(
+                "Solve time: {} ms ({:.0} nodes/s)",
+                outcome.solve_duration_ms, outcome.nodes_per_second
+            );
 
             if outcome.is_win {
                 if let Some(line) = outcome.winning_line.as_ref() {
                     println!("\nWinning line length: {}", line.len());
 
                     if pysol_output_mode == PysolOutputMode::Moves {
-                        // Replay for context-dependent move descriptions.
-                        let mut replay = GameState::new(spec.deck);
-                        for (mi, mv) in line.iter().enumerate() {
-                            let tab = replay.current_tableau();
-                            println!("  {:3}: {}", mi + 1, mv.describe(&tab));
-                            replay.apply_move(*mv);
-                        }
-                        debug_assert!(replay.current_tableau().is_win());
+                        print!("{}", display::render_move_sequence(spec.deck, line, cfg.draw_rule));
                     }
                 } else {
                     println!("\n(internal error) win reported but no winning_line recorded");
@@ -277,36 +557,147 @@ pub fn run() {
             println!();
         }
 
+        let elapsed = batch_start.elapsed();
+
+        if pysol_stats {
+            print!("{}", pysol_stats_report(&batch));
+            println!();
+        }
+
+        if silent {
+            println!("{}", batch.stats);
+            return;
+        }
+
+        println!("=== PySol batch stats ===");
+        if parallel {
+            println!(
+                "Wall-clock time: {:.2?} (parallel; rerun without --parallel to compare)",
+                elapsed
+            );
+        } else {
+            println!(
+                "Wall-clock time: {:.2?} (sequential; rerun with --parallel to compare)",
+                elapsed
+            );
+        }
+        batch.print_summary();
+        batch.stats.print_histogram();
+
         return;
     }
 
-    // --- Normal solver path: build a pseudo-random starting deck from `--seed` ---
-    let deck: [card::Card; CARDS_PER_DECK as usize] = card::shuffled_deck_from_seed(seed);
-
-    let outcome = search::solve_single_deck_with_config(deck, &cfg);
-
-    println!("Deck seed: {}", seed);
-    println!("Nodes visited: {}", outcome.nodes_visited);
-    println!("Win? {}", outcome.is_win);
-    println!("Termination reason: {:?}", outcome.termination);
-    println!("Max branch depth (moves): {}", outcome.max_branch_depth);
-    println!("Max shelved states: {}", outcome.max_shelved);
-    println!("Dead-end branches: {}", outcome.dead_end_branches);
-    println!("Loop-pruned branches: {}", outcome.loop_pruned_branches);
-
-    if outcome.is_win {
-        if let Some(line) = outcome.winning_line.as_ref() {
-            println!("Winning line length: {}", line.len());
-            if print_winning_moves {
-                println!("Winning moves:");
-                let mut replay = GameState::new(deck);
-                for (i, mv) in line.iter().enumerate() {
-                    let tab = replay.current_tableau();
-                    println!("  {:3}: {}", i + 1, mv.describe(&tab));
-                    replay.apply_move(*mv);
-                }
-                debug_assert!(replay.current_tableau().is_win());
-            }
+    // --- Seed range: one terse line per seed plus aggregate stats ---
+    if let Some((start, end)) = seed_range {
+        let range_stats = solve_seed_range(start, end, &cfg);
+        if silent {
+            println!("{}", range_stats);
+            return;
         }
+        println!();
+        println!("=== Seed range {}:{} stats ===", start, end);
+        print!("{}", range_stats.summary_string());
+        return;
+    }
+
+    // --- Normal solver path: build a pseudo-random starting deck from `--seed` ---
+    let deck: [card::Card; CARDS_PER_DECK as usize] = deck_for_seed(seed);
+
+    let outcome = cfg.strategy.solve(deck, &cfg);
+
+    #[cfg(debug_assertions)]
+    if let Some(line) = outcome.winning_line.as_ref()
+        && let Err(e) = search::validate_winning_line(&deck, line, cfg.draw_rule)
+    {
+        panic!("reported win for seed {} failed validation: {}", seed, e);
+    }
+
+    print!("{}", seed_report(seed, &outcome, detail));
+
+    if !silent
+        && print_winning_moves
+        && outcome.is_win
+        && let Some(line) = outcome.winning_line.as_ref()
+    {
+        println!("Winning moves:");
+        print!("{}", display::render_move_sequence(deck, line, cfg.draw_rule));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_seed_range_accepts_start_colon_end() {
+        assert_eq!(parse_seed_range("1:10"), Ok((1u32, 10)));
+        assert_eq!(parse_seed_range("7:7"), Ok((7u32, 7)));
+        assert!(parse_seed_range::<u32>("10:1").is_err());
+        assert!(parse_seed_range::<u32>("1-10").is_err());
+        assert!(parse_seed_range::<u32>("1:x").is_err());
+        assert!(parse_seed_range::<u32>("1:5000000000").is_err());
+        assert_eq!(
+            parse_seed_range("1:5000000000"),
+            Ok((1u64, 5_000_000_000))
+        );
+    }
+
+    /// Smoke test for `--range-seeds=1:10` with a small node budget.
+    #[test]
+    fn seed_range_1_to_10_records_every_seed() {
+        let (start, end) = parse_seed_range("1:10").unwrap();
+        let cfg = search::SearchConfig::builder()
+            .limits(search::SearchLimits::builder().max_nodes(2_000).build())
+            .build();
+        let range_stats = solve_seed_range(start, end, &cfg);
+        println!("{}", range_stats);
+        assert_eq!(range_stats.games_played, 10);
+        assert_eq!(range_stats.games_won + range_stats.games_lost, 10);
+        assert!(range_stats.mean_nodes_visited() >= 1.0);
+    }
+
+    /// `--verbose=0` prints a single line for a `--seed` run; the default
+    /// level prints the full statistics block.
+    #[test]
+    fn silent_seed_report_is_one_line() {
+        let detail = search::DetailLevel::from_verbosity(0).unwrap();
+        assert_eq!(detail, search::DetailLevel::Silent);
+        let cfg = search::SearchConfig::builder()
+            .detail(detail)
+            .limits(search::SearchLimits::builder().max_nodes(2_000).build())
+            .build();
+        let outcome = cfg.strategy.solve(card::shuffled_deck_from_seed(5), &cfg);
+
+        let silent = seed_report(5, &outcome, detail);
+        print!("{}", silent);
+        assert_eq!(silent.lines().count(), 1);
+        assert_eq!(silent, format!("seed 5: {}\n", outcome.terse_summary()));
+
+        let summary = seed_report(5, &outcome, search::DetailLevel::Summary);
+        assert!(summary.lines().count() > 1);
+        assert!(summary.starts_with("Deck seed: 5\n"));
+
+        assert_eq!(search::DetailLevel::from_verbosity(3), Some(search::DetailLevel::Trace));
+        assert_eq!(search::DetailLevel::from_verbosity(4), None);
+    }
+
+    /// `--pysol-stats` block for three seed-derived decks.
+    #[test]
+    fn pysol_stats_report_has_win_rate() {
+        let decks: Vec<pysol_decks::DeckSpec> = ["1", "2", "3"]
+            .iter()
+            .map(|s| pysol_decks::deck_from_pysol_seed_str(s).unwrap())
+            .collect();
+        let cfg = search::SearchConfig::builder()
+            .detail(search::DetailLevel::Silent)
+            .limits(search::SearchLimits::builder().max_nodes(5_000).build())
+            .build();
+        let batch = search::solve_batch_sequential(&decks, &cfg, None);
+
+        let report = pysol_stats_report(&batch);
+        print!("{}", report);
+        assert!(report.contains("Decks run: 3\n"));
+        assert!(report.lines().any(|l| l.starts_with("Win rate: ") && l.ends_with('%')));
+        assert!(report.contains("Longest winning line: "));
     }
 }
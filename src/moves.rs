@@ -1,12 +1,16 @@
-//! Move representation and move generation for Klondike (draw-3, unlimited redeals).
+//! Move representation and move generation for Klondike (draw-1 or draw-3,
+//! unlimited redeals).
 //
 //! This module defines a compact `Move` type plus helpers to generate all
 //! legal moves from a given `Tableau`, plus an `apply` method that mutates
 //! a tableau in-place according to a chosen move. Higher-level search code
 //! can combine these to explore the game tree.
 
-use crate::card::{Card, Suit};
-use crate::tableau::{Tableau, NUM_COLS};
+use core::cmp::Ordering;
+
+use crate::card::{Card, Rank, Suit};
+use crate::parse::MoveParseError;
+use crate::tableau::{Tableau, MAX_COL, NUM_COLS, NUM_FOUNDATIONS};
 
 /// Number of ranks per suit in a standard deck.
 ///
@@ -20,7 +24,13 @@ const RANKS_PER_SUIT: u8 = 13;
 /// This is designed to be compact but still readable when logged. The
 /// `src_col` / `dst_col` indices are 0-based internally but usually printed
 /// as 1-based when shown to a human.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///
+/// `Ord` is a canonical order for deterministic listings, not a search
+/// priority: `ColumnToFoundation < WasteToFoundation < ColumnToColumn <
+/// WasteToColumn < FlipColumn < DealFromStock < RedealStock <
+/// FoundationToColumn`, then by the variant's fields in declaration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MoveKind {
     /// Move a run of face-up cards within the tableau from one column to another.
     ///
@@ -47,6 +57,19 @@ pub enum MoveKind {
     /// Move the top card of the waste pile to its foundation.
     WasteToFoundation,
 
+    /// Move the top card of a foundation back onto a tableau column.
+    ///
+    /// Standard Klondike forbids this, so `generate_legal_moves` never
+    /// lists it; searches add it via `generate_foundation_to_column_moves`
+    /// only when `SearchConfig::allow_foundation_to_tableau` is set.
+    ///
+    /// - `foundation_idx`: which foundation (same suit order as `Suit::ALL`)
+    /// - `dst_col`: which column to move to (0..NUM_COLS-1)
+    FoundationToColumn {
+        foundation_idx: u8,
+        dst_col: u8,
+    },
+
     /// Flip the top card of a column from face-down to face-up.
     ///
     /// This is applicable when the column has cards but they are all
@@ -57,9 +80,15 @@ pub enum MoveKind {
         col: u8,
     },
 
-    /// Deal cards from the stock to the waste (draw-3, or fewer if stock
-    /// has < 3 cards remaining).
-    DealFromStock,
+    /// Deal cards from the stock to the waste: one card under draw-1, or
+    /// three under draw-3 (fewer if the stock has < 3 cards remaining).
+    ///
+    /// `cards_drawn` (1, 2 or 3) is how many cards the deal turns over:
+    /// the tableau's `draw_rule` count, capped at the stock size. It is
+    /// fixed when the move is generated (see `Move::deal_from_stock`).
+    DealFromStock {
+        cards_drawn: u8,
+    },
 
     /// Redeal: when the stock is empty and the waste is non-empty, flip
     /// the waste back into the stock (face-down) preserving order.
@@ -70,8 +99,39 @@ pub enum MoveKind {
     RedealStock,
 }
 
+impl MoveKind {
+    /// `(variant rank, fields...)` key behind the canonical `Ord`.
+    fn ordering_key(&self) -> (u8, u8, u8, u8) {
+        match *self {
+            MoveKind::ColumnToFoundation { src_col } => (0, src_col, 0, 0),
+            MoveKind::WasteToFoundation => (1, 0, 0, 0),
+            MoveKind::ColumnToColumn { src_col, src_index, dst_col } => (2, src_col, src_index, dst_col),
+            MoveKind::WasteToColumn { dst_col } => (3, dst_col, 0, 0),
+            MoveKind::FlipColumn { col } => (4, col, 0, 0),
+            MoveKind::DealFromStock { cards_drawn } => (5, cards_drawn, 0, 0),
+            MoveKind::RedealStock => (6, 0, 0, 0),
+            MoveKind::FoundationToColumn { foundation_idx, dst_col } => (7, foundation_idx, dst_col, 0),
+        }
+    }
+}
+
+impl PartialOrd for MoveKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MoveKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ordering_key().cmp(&other.ordering_key())
+    }
+}
+
 /// A single move, wrapping a `MoveKind` for future extensibility.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///
+/// Ordered and hashed by its `kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move {
     pub kind: MoveKind,
 }
@@ -80,7 +140,7 @@ pub struct Move {
 
 /// Return a 0-based rank index for a card (0=Ace, 12=King).
 #[inline]
-fn rank_index(card: Card) -> u8 {
+pub(crate) fn rank_index(card: Card) -> u8 {
     card.0 % RANKS_PER_SUIT
 }
 
@@ -125,14 +185,23 @@ fn foundation_index_for(card: Card) -> usize {
 ///   foundations[i] = 0..=13
 /// where 0 means empty, and N>0 means the top card has rank index N-1
 /// (0=Ace, 12=King).
-fn can_move_to_foundation(tab: &Tableau, card: Card) -> bool {
+#[inline]
+pub(crate) fn can_move_to_foundation(tab: &Tableau, card: Card) -> bool {
     let f_idx = foundation_index_for(card);
     let top = tab.foundations[f_idx];
-    let r_idx = rank_index(card);
+    let rank = card.rank();
 
     match top {
-        0 => r_idx == 0,          // empty foundation: only Ace (0) allowed
-        n => r_idx == n,          // existing top is n -> next rank index must be n
+        0 => rank == Rank::Ace,                       // empty foundation: only Ace allowed
+        n => Rank::from_u8(n - 1).is_one_below(rank), // next rank after the top
+    }
+}
+
+/// Top card of foundation `f_idx`, or `None` if that foundation is empty.
+fn foundation_top(tab: &Tableau, f_idx: usize) -> Option<Card> {
+    match tab.foundations[f_idx] {
+        0 => None,
+        n => Some(Card::new(Suit::ALL[f_idx], Rank::from_u8(n - 1))),
     }
 }
 
@@ -141,26 +210,26 @@ fn can_move_to_foundation(tab: &Tableau, card: Card) -> bool {
 /// In Klondike, this requires:
 ///   - colors are opposite (red on black or black on red), and
 ///   - rank(below) = rank(above) + 1
-fn can_place_on_column(below: Card, above: Card) -> bool {
-    colors_differ(below, above) && rank_index(below) == rank_index(above) + 1
+#[inline]
+pub(crate) fn can_place_on_column(below: Card, above: Card) -> bool {
+    colors_differ(below, above) && above.rank().is_one_below(below.rank())
 }
 
 /// True if the slice of cards (top-to-bottom) forms a valid descending,
 /// alternating-color run suitable for moving as a block.
 ///
 /// The slice is assumed to be ordered from top (index 0) to bottom (last).
-fn is_valid_run(cards: &[Card]) -> bool {
+/// An empty slice is not a run.
+pub fn is_valid_run(cards: &[Card]) -> bool {
     if cards.is_empty() {
         return false;
     }
     for pair in cards.windows(2) {
         let top = pair[0];   // closer to the top of the column
         let below = pair[1]; // physically lower card
-        let r_top = rank_index(top);
-        let r_below = rank_index(below);
 
         // We require a descending run, so top rank = below rank + 1.
-        if r_top != r_below + 1 {
+        if !below.rank().is_one_below(top.rank()) {
             return false;
         }
         if !colors_differ(top, below) {
@@ -176,7 +245,7 @@ fn is_valid_run(cards: &[Card]) -> bool {
 ///
 /// This does **not** apply or prioritize moves; it just lists everything that
 /// is legal in the current state. A search module can then choose which move
-/// to try first.
+/// to try first, e.g. via `generate_legal_moves_ordered`.
 ///
 /// The rule set implemented here:
 ///   - Column -> Foundation (top face-up card only)
@@ -186,23 +255,96 @@ fn is_valid_run(cards: &[Card]) -> bool {
 ///   - FlipColumn when a column has cards but all face-down
 ///   - DealFromStock when stock is non-empty
 ///   - RedealStock when stock is empty and waste is non-empty
+///
+/// No redeal limit is applied; see `generate_legal_moves_with_limits`.
 pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
+    generate_legal_moves_with_limits(tab, 0, None)
+}
+
+/// `generate_legal_moves`, with `RedealStock` removed once `redeals_used`
+/// has reached `max_redeals` (`None` = unlimited redeals).
+///
+/// A tableau does not record how many redeals led to it, so the caller
+/// supplies the count (e.g. `GameState::redeals_used`).
+pub fn generate_legal_moves_with_limits(
+    tab: &Tableau,
+    redeals_used: u8,
+    max_redeals: Option<u8>,
+) -> Vec<Move> {
     let mut moves = Vec::new();
+    generate_legal_moves_with_limits_into(tab, redeals_used, max_redeals, &mut moves);
+    moves
+}
 
-    // Column -> Foundation
-    for col_idx in 0..NUM_COLS {
-        let col = &tab.columns[col_idx];
-        if col.len == 0 {
-            continue;
-        }
-        if col.len <= col.num_face_down {
-            // no face-up card
-            continue;
+/// Same as `generate_legal_moves_with_limits`, but writes into `buf`
+/// (cleared first) instead of allocating.
+pub fn generate_legal_moves_with_limits_into(
+    tab: &Tableau,
+    redeals_used: u8,
+    max_redeals: Option<u8>,
+    buf: &mut Vec<Move>,
+) {
+    generate_legal_moves_into(tab, buf);
+    if !redeal_allowed(redeals_used, max_redeals) {
+        buf.retain(|mv| !matches!(mv.kind, MoveKind::RedealStock));
+    }
+}
+
+/// The redeal-limit rule: another `RedealStock` is allowed after
+/// `redeals_used` redeals unless that has reached `max_redeals`
+/// (`None` = unlimited redeals).
+pub fn redeal_allowed(redeals_used: u8, max_redeals: Option<u8>) -> bool {
+    max_redeals.is_none_or(|max| redeals_used < max)
+}
+
+/// Same as `generate_legal_moves`, but writes into `buf` instead of
+/// allocating: `buf` is cleared first and keeps its capacity, so a search
+/// can reuse one buffer for every node it expands.
+pub fn generate_legal_moves_into(tab: &Tableau, buf: &mut Vec<Move>) {
+    buf.clear();
+    push_legal_moves(tab, |mv| buf.push(mv));
+}
+
+/// Upper bound on the number of moves `generate_legal_moves` can return
+/// for any position: 8 foundation moves (7 columns + waste), 20
+/// column-to-column moves (each non-empty destination accepts only the two
+/// cards one rank lower of the opposite colour, and each of the 4 Kings
+/// can go to each empty column, which peaks at 3 empty columns: 2*4 + 4*3),
+/// 7 waste-to-column moves, 7 flips and 1 deal or redeal. Real positions
+/// stay far below this.
+pub const MAX_LEGAL_MOVES: usize = 43;
+
+/// `generate_legal_moves` without a heap allocation: fills `buf` from the
+/// start and returns how many moves were written (`buf[..n]`, in the same
+/// order as `generate_legal_moves`).
+///
+/// `buf` has room for more than `MAX_LEGAL_MOVES`, so it cannot fill up;
+/// if it ever did, debug builds panic and release builds drop the extra
+/// moves rather than write past the end.
+pub fn generate_legal_moves_no_alloc(tab: &Tableau, buf: &mut [Move; 64]) -> usize {
+    let mut count = 0;
+    push_legal_moves(tab, |mv| {
+        debug_assert!(count < buf.len(), "more than {} legal moves", buf.len());
+        if let Some(slot) = buf.get_mut(count) {
+            *slot = mv;
+            count += 1;
         }
-        let top_idx = col.len - 1;
-        let card = col.cards[top_idx as usize];
+    });
+    count
+}
+
+/// The move generator behind `generate_legal_moves_into` and
+/// `generate_legal_moves_no_alloc`: calls `push` once per legal move.
+fn push_legal_moves(tab: &Tableau, mut push: impl FnMut(Move)) {
+    // Only the per-pile part of `is_column_invariant_valid`: hand-built
+    // test positions often hold fewer than 52 cards.
+    #[cfg(debug_assertions)]
+    debug_assert!(tab.piles_are_well_formed(), "generate_legal_moves on a malformed tableau");
+
+    // Column -> Foundation
+    for (col_idx, card) in tab.iter_face_up_tops() {
         if can_move_to_foundation(tab, card) {
-            moves.push(Move {
+            push(Move {
                 kind: MoveKind::ColumnToFoundation {
                     src_col: col_idx as u8,
                 },
@@ -211,24 +353,21 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
     }
 
     // Waste -> Foundation
-    if let Some(card) = tab.waste.top() {
-        if can_move_to_foundation(tab, card) {
-            moves.push(Move {
-                kind: MoveKind::WasteToFoundation,
-            });
-        }
+    if let Some(card) = tab.waste.top()
+        && can_move_to_foundation(tab, card)
+    {
+        push(Move {
+            kind: MoveKind::WasteToFoundation,
+        });
     }
 
     // Column -> Column (runs)
     for src_col_idx in 0..NUM_COLS {
-        let col = &tab.columns[src_col_idx];
-        if col.len == 0 {
-            continue;
-        }
-        if col.len <= col.num_face_down {
-            // all face-down; no movable run
+        if tab.column_top_face_up_card(src_col_idx).is_none() {
+            // empty or all face-down; no movable run
             continue;
         }
+        let col = &tab.columns[src_col_idx];
 
         let len = col.len as usize;
         let first_face_up = col.num_face_down as usize;
@@ -251,23 +390,8 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
 
                 if dst.len == 0 {
                     // Empty column: only runs starting with King can move here.
-                    if rank_index(run_top_card) == 12 {
-                        moves.push(Move {
-                            kind: MoveKind::ColumnToColumn {
-                                src_col: src_col_idx as u8,
-                                src_index: start as u8,
-                                dst_col: dst_col_idx as u8,
-                            },
-                        });
-                    }
-                } else {
-                    if dst.len <= dst.num_face_down {
-                        // destination top card is still face-down
-                        continue;
-                    }
-                    let dst_top = dst.cards[(dst.len - 1) as usize];
-                    if can_place_on_column(dst_top, run_top_card) {
-                        moves.push(Move {
+                    if run_top_card.rank() == Rank::King {
+                        push(Move {
                             kind: MoveKind::ColumnToColumn {
                                 src_col: src_col_idx as u8,
                                 src_index: start as u8,
@@ -275,6 +399,16 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
                             },
                         });
                     }
+                } else if let Some(dst_top) = tab.column_top_face_up_card(dst_col_idx)
+                    && can_place_on_column(dst_top, run_top_card)
+                {
+                    push(Move {
+                        kind: MoveKind::ColumnToColumn {
+                            src_col: src_col_idx as u8,
+                            src_index: start as u8,
+                            dst_col: dst_col_idx as u8,
+                        },
+                    });
                 }
             }
         }
@@ -287,8 +421,8 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
 
             if dst.len == 0 {
                 // Empty column: only King can move here.
-                if rank_index(card) == 12 {
-                    moves.push(Move {
+                if card.rank() == Rank::King {
+                    push(Move {
                         kind: MoveKind::WasteToColumn {
                             dst_col: dst_col_idx as u8,
                         },
@@ -300,7 +434,7 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
                 }
                 let dst_top = dst.cards[(dst.len - 1) as usize];
                 if can_place_on_column(dst_top, card) {
-                    moves.push(Move {
+                    push(Move {
                         kind: MoveKind::WasteToColumn {
                             dst_col: dst_col_idx as u8,
                         },
@@ -314,7 +448,7 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
     for col_idx in 0..NUM_COLS {
         let col = &tab.columns[col_idx];
         if col.len > 0 && col.num_face_down == col.len {
-            moves.push(Move {
+            push(Move {
                 kind: MoveKind::FlipColumn {
                     col: col_idx as u8,
                 },
@@ -328,28 +462,338 @@ pub fn generate_legal_moves(tab: &Tableau) -> Vec<Move> {
 
     if stock_len > 0 {
         // There are still cards in stock: we can deal.
-        moves.push(Move {
-            kind: MoveKind::DealFromStock,
-        });
+        push(Move::deal_from_stock(tab));
     } else if stock_len == 0 && waste_len > 0 {
         // Stock empty, but waste not: redeal is allowed.
-        moves.push(Move {
+        push(Move {
             kind: MoveKind::RedealStock,
         });
     }
+}
+
+/// True if `generate_legal_moves(tab)` would return at least one move.
+///
+/// Applies the same rules but stops at the first legal move found and
+/// never allocates, so it is cheap enough to call on every search node.
+/// The cheapest checks (stock and waste) come first.
+pub(crate) fn can_make_any_move(tab: &Tableau) -> bool {
+    // DealFromStock or RedealStock.
+    if !tab.stock.is_empty() || !tab.waste.is_empty() {
+        return true;
+    }
+    // The waste is empty from here on, so only column moves remain.
+
+    for src in &tab.columns {
+        if src.len == 0 {
+            continue;
+        }
+        // FlipColumn
+        if src.num_face_down == src.len {
+            return true;
+        }
+        let len = src.len as usize;
+        // Column -> Foundation
+        if can_move_to_foundation(tab, src.cards[len - 1]) {
+            return true;
+        }
+        // Column -> Column
+        for start in src.num_face_down as usize..len {
+            if !is_valid_run(&src.cards[start..len]) {
+                continue;
+            }
+            let run_top_card = src.cards[start];
+            for dst in &tab.columns {
+                if core::ptr::eq(dst, src) {
+                    continue;
+                }
+                if dst.len == 0 {
+                    if run_top_card.rank() == Rank::King {
+                        return true;
+                    }
+                } else if dst.len > dst.num_face_down
+                    && can_place_on_column(dst.cards[(dst.len - 1) as usize], run_top_card)
+                {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+// ----- Move ordering -----
+
+/// Policy for ordering the moves returned by `generate_legal_moves_ordered`.
+///
+/// All policies return the same set of moves; they differ only in which
+/// moves a search will try first. Sorting is stable, so moves with equal
+/// priority keep their structural order from `generate_legal_moves`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MovePolicy {
+    /// Moves to the foundations first (lowest rank first), then everything
+    /// else. Playing to the foundations is almost always safe, so this is
+    /// the default for DFS.
+    #[default]
+    FoundationFirst,
+    /// Moves that turn a face-down column card face-up first, then moves
+    /// to the foundations, then everything else.
+    ExposeHidden,
+    /// Structural order exactly as produced by `generate_legal_moves`.
+    Unordered,
+}
 
+/// Legal moves for one tableau, sorted according to a `MovePolicy`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrderedMoveList {
+    moves: Vec<Move>,
+}
+
+impl OrderedMoveList {
+    /// Number of moves in the list.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// True if there are no legal moves.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The moves, best first.
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Iterate over the moves, best first.
+    pub fn iter(&self) -> std::slice::Iter<'_, Move> {
+        self.moves.iter()
+    }
+}
+
+impl IntoIterator for OrderedMoveList {
+    type Item = Move;
+    type IntoIter = std::vec::IntoIter<Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.into_iter()
+    }
+}
+
+/// True if the move puts a card on a foundation.
+fn is_foundation_move(mv: &Move) -> bool {
+    matches!(
+        mv.kind,
+        MoveKind::ColumnToFoundation { .. } | MoveKind::WasteToFoundation
+    )
+}
+
+/// True if applying the move turns a face-down column card face-up, either
+/// directly (`FlipColumn`) or by removing a column's last face-up card.
+fn exposes_hidden_card(tab: &Tableau, mv: &Move) -> bool {
+    match mv.kind {
+        MoveKind::FlipColumn { .. } => true,
+        MoveKind::ColumnToColumn {
+            src_col, src_index, ..
+        } => {
+            let col = &tab.columns[src_col as usize];
+            col.num_face_down > 0 && src_index == col.num_face_down
+        }
+        MoveKind::ColumnToFoundation { src_col } => {
+            let col = &tab.columns[src_col as usize];
+            col.num_face_down > 0 && col.num_face_up() == 1
+        }
+        _ => false,
+    }
+}
+
+/// Rank index of the card a foundation move would play (0 = Ace).
+fn foundation_move_rank(tab: &Tableau, mv: &Move) -> u8 {
+    let card = match mv.kind {
+        MoveKind::ColumnToFoundation { src_col } => tab.columns[src_col as usize].top(),
+        MoveKind::WasteToFoundation => tab.waste.top(),
+        _ => None,
+    };
+    card.map(rank_index).unwrap_or(RANKS_PER_SUIT)
+}
+
+/// Generate the relaxed-rule moves that take a foundation's top card back
+/// onto a tableau column (`MoveKind::FoundationToColumn`).
+///
+/// A move is listed when the foundation top can go onto a column's face-up
+/// top card according to `can_place_on_column`. These are kept separate
+/// from `generate_legal_moves` because standard Klondike forbids them.
+pub fn generate_foundation_to_column_moves(tab: &Tableau) -> Vec<Move> {
+    let mut moves = Vec::new();
+    for f_idx in 0..NUM_FOUNDATIONS {
+        let Some(card) = foundation_top(tab, f_idx) else {
+            continue;
+        };
+        for dst_col_idx in 0..NUM_COLS {
+            let dst = &tab.columns[dst_col_idx];
+            if dst.len <= dst.num_face_down {
+                // Empty, or top card still face-down.
+                continue;
+            }
+            let dst_top = dst.cards[(dst.len - 1) as usize];
+            if can_place_on_column(dst_top, card) {
+                moves.push(Move {
+                    kind: MoveKind::FoundationToColumn {
+                        foundation_idx: f_idx as u8,
+                        dst_col: dst_col_idx as u8,
+                    },
+                });
+            }
+        }
+    }
+    moves
+}
+
+/// Drop `ColumnToColumn` moves into empty columns that only differ from
+/// another listed move by which empty column they use.
+///
+/// Column indices are part of the tableau hash, so sending the same King
+/// run to empty column 3 or empty column 5 gives two "different" states
+/// that are really the same position with the columns relabelled. This
+/// keeps only the move to the lowest-indexed empty column for each run.
+///
+/// A run that is the whole source column (`src_index == 0`) going to an
+/// empty column just swaps an empty column with a full one, so those moves
+/// are dropped altogether. This also covers the "King from column A or B
+/// to empty column C" case: two sources can never hold the same run (every
+/// card is unique), so the only symmetric pair is a column trading places
+/// with an empty one.
+///
+/// Every other move is kept, in its original order.
+pub fn prune_symmetric_column_moves(tab: &Tableau, moves: Vec<Move>) -> Vec<Move> {
+    let first_empty = tab.columns.iter().position(|col| col.len == 0);
     moves
+        .into_iter()
+        .filter(|mv| match mv.kind {
+            MoveKind::ColumnToColumn {
+                src_index, dst_col, ..
+            } if tab.columns[dst_col as usize].len == 0 => {
+                src_index != 0 && first_empty == Some(dst_col as usize)
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// The foundation move playing the lowest-ranked card that fits, from the
+/// waste top or any column top, or `None` if no card can go up.
+///
+/// This is a cheap shortcut for `Tableau::auto_complete_moves`, which calls
+/// it once per step and does not need the full legal move list.
+pub(crate) fn lowest_foundation_move(tab: &Tableau) -> Option<Move> {
+    let waste = tab
+        .waste
+        .top()
+        .map(|card| (card, MoveKind::WasteToFoundation));
+    let columns = tab.columns.iter().enumerate().filter_map(|(i, col)| {
+        col.top()
+            .map(|card| (card, MoveKind::ColumnToFoundation { src_col: i as u8 }))
+    });
+    waste
+        .into_iter()
+        .chain(columns)
+        .filter(|&(card, _)| can_move_to_foundation(tab, card))
+        .min_by_key(|&(card, _)| rank_index(card))
+        .map(|(_, kind)| Move { kind })
+}
+
+/// Generate all legal moves from the given tableau, ordered by `policy`.
+///
+/// This is `generate_legal_moves` followed by a stable sort, so it lists
+/// exactly the same moves.
+pub fn generate_legal_moves_ordered(tab: &Tableau, policy: MovePolicy) -> OrderedMoveList {
+    let mut moves = generate_legal_moves(tab);
+    order_moves(tab, policy, &mut moves);
+    OrderedMoveList { moves }
+}
+
+/// Stable-sort `moves` (legal moves from `tab`) according to `policy`.
+///
+/// This is the ordering step of `generate_legal_moves_ordered`, for callers
+/// that fill their own buffer with `generate_legal_moves_into`.
+pub fn order_moves(tab: &Tableau, policy: MovePolicy, moves: &mut [Move]) {
+    match policy {
+        MovePolicy::FoundationFirst => {
+            moves.sort_by_key(|mv| {
+                if is_foundation_move(mv) {
+                    (0, foundation_move_rank(tab, mv))
+                } else {
+                    (1, 0)
+                }
+            });
+        }
+        MovePolicy::ExposeHidden => {
+            moves.sort_by_key(|mv| {
+                if exposes_hidden_card(tab, mv) {
+                    0
+                } else if is_foundation_move(mv) {
+                    1
+                } else {
+                    2
+                }
+            });
+        }
+        MovePolicy::Unordered => {}
+    }
 }
 
 // ----- Mutating application of a move -----
 
+/// What `Move::apply` did that cannot be read back off the tableau it
+/// leaves behind. `Move::unapply` needs it to restore the tableau exactly:
+/// e.g. after a column's last face-up card leaves, the tableau no longer
+/// shows whether the new top card was turned up by the move or already
+/// face-up, and after a short draw-3 deal it does not show how many cards
+/// were dealt.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UndoInfo {
+    /// Cards the move carried: run length, cards dealt or redealt, or 1
+    /// for single-card moves. 0 if `apply` left the tableau unchanged.
+    pub cards_moved: u8,
+    /// True if a face-down column card was turned face-up, either by
+    /// `FlipColumn` or because the move uncovered it.
+    pub flipped: bool,
+    /// Foundation a card was played to (`ColumnToFoundation`,
+    /// `WasteToFoundation`).
+    pub foundation_idx: u8,
+}
+
 impl Move {
+    /// The `DealFromStock` move from `tab`: it draws the draw rule's
+    /// `cards_per_deal`, or the whole stock if fewer cards are left.
+    pub fn deal_from_stock(tab: &Tableau) -> Move {
+        let cards_drawn = tab.draw_rule.cards_per_deal().min(tab.stock.len());
+        Move {
+            kind: MoveKind::DealFromStock { cards_drawn },
+        }
+    }
+
+    /// This move with a `DealFromStock` count corrected for `tab` (see
+    /// `deal_from_stock`); any other move is returned unchanged. Notation
+    /// read from a file may not say how many cards a deal drew (a bare `D`
+    /// reads as 3), so replay code fixes the count up before checking the
+    /// move against `generate_legal_moves`.
+    pub fn with_deal_count_for(self, tab: &Tableau) -> Move {
+        match self.kind {
+            MoveKind::DealFromStock { .. } => Move::deal_from_stock(tab),
+            _ => self,
+        }
+    }
+
     /// Apply this move to the given tableau, mutating it in-place.
     ///
     /// This function assumes the move is legal in the given state. It does
     /// not re-check legality; callers should rely on `generate_legal_moves`
     /// to produce only valid moves.
-    pub fn apply(&self, tab: &mut Tableau) {
+    ///
+    /// Returns the `UndoInfo` needed to reverse the move with `unapply`;
+    /// callers that never undo can ignore it.
+    pub fn apply(&self, tab: &mut Tableau) -> UndoInfo {
+        let mut undo = UndoInfo::default();
         match self.kind {
             MoveKind::ColumnToColumn {
                 src_col,
@@ -360,8 +804,9 @@ impl Move {
                 let d = dst_col as usize;
                 if s == d {
                     // Should never happen for legal moves; ignore defensively.
-                    return;
+                    return undo;
                 }
+                let (src_len, src_face_down) = (tab.columns[s].len, tab.columns[s].num_face_down);
 
                 // Split the columns slice to obtain two distinct mutable references.
                 if s < d {
@@ -375,22 +820,28 @@ impl Move {
                     let src = &mut right[0];
                     move_run_between_columns(src, dst, src_index);
                 }
+                undo.cards_moved = src_len - tab.columns[s].len;
+                undo.flipped = tab.columns[s].num_face_down < src_face_down;
             }
 
             MoveKind::ColumnToFoundation { src_col } => {
                 let s = src_col as usize;
                 let col = &mut tab.columns[s];
                 if col.len == 0 {
-                    return;
+                    return undo;
                 }
+                let src_face_down = col.num_face_down;
                 let top_idx = col.len - 1;
                 let card = col.cards[top_idx as usize];
                 // Remove the card from the column.
                 col.len -= 1;
                 flip_exposed_card_after_removal(col);
+                undo.flipped = col.num_face_down < src_face_down;
                 let f_idx = foundation_index_for(card);
                 let r_idx = rank_index(card);
                 tab.foundations[f_idx] = r_idx + 1;
+                undo.cards_moved = 1;
+                undo.foundation_idx = f_idx as u8;
             }
 
             MoveKind::WasteToColumn { dst_col } => {
@@ -401,6 +852,7 @@ impl Move {
                     dst.cards[dst_len] = card;
                     dst.len += 1;
                     // New card is face-up; num_face_down unchanged.
+                    undo.cards_moved = 1;
                 }
             }
 
@@ -409,6 +861,23 @@ impl Move {
                     let f_idx = foundation_index_for(card);
                     let r_idx = rank_index(card);
                     tab.foundations[f_idx] = r_idx + 1;
+                    undo.cards_moved = 1;
+                    undo.foundation_idx = f_idx as u8;
+                }
+            }
+
+            MoveKind::FoundationToColumn {
+                foundation_idx,
+                dst_col,
+            } => {
+                let f = foundation_idx as usize;
+                if let Some(card) = foundation_top(tab, f) {
+                    tab.foundations[f] -= 1;
+                    let dst = &mut tab.columns[dst_col as usize];
+                    let dst_len = dst.len as usize;
+                    dst.cards[dst_len] = card;
+                    dst.len += 1;
+                    undo.cards_moved = 1;
                 }
             }
 
@@ -420,13 +889,17 @@ impl Move {
                     // count of face-down cards. The actual card data in
                     // `cards[]` does not change.
                     col_ref.num_face_down -= 1;
+                    undo.flipped = true;
                 }
             }
 
-            MoveKind::DealFromStock => {
-                // Draw up to 3 cards from stock, pushing them onto waste.
+            MoveKind::DealFromStock { .. } => {
+                // Draw up to `cards_per_deal` cards from stock (1 or 3,
+                // depending on the draw rule), pushing them onto waste.
+                // For a legal move this is exactly `cards_drawn`.
+                let per_deal = tab.draw_rule.cards_per_deal();
                 let mut drawn = 0;
-                while drawn < 3 {
+                while drawn < per_deal {
                     if let Some(card) = tab.stock.pop() {
                         tab.waste.push(card);
                         drawn += 1;
@@ -434,6 +907,7 @@ impl Move {
                         break;
                     }
                 }
+                undo.cards_moved = drawn;
             }
 
             MoveKind::RedealStock => {
@@ -442,6 +916,7 @@ impl Move {
                 // to stock restores the original stock order.
                 while let Some(card) = tab.waste.pop() {
                     tab.stock.push(card);
+                    undo.cards_moved += 1;
                 }
             }
         }
@@ -457,6 +932,104 @@ impl Move {
             }
         }
 
+        undo
+    }
+
+    /// Reverse this move in-place, given the `UndoInfo` that `apply`
+    /// returned for it.
+    ///
+    /// `tab` must be exactly the tableau `apply` left behind; afterwards it
+    /// equals the tableau from before `apply`. This lets a search walk back
+    /// up a branch without cloning a `GameState` per node.
+    ///
+    /// The cards involved are read off the tableau (a foundation's top
+    /// card, the top of a column or the waste), so only what the tableau
+    /// cannot show is taken from `undo`.
+    pub fn unapply(&self, tab: &mut Tableau, undo: UndoInfo) {
+        if undo.cards_moved == 0 && !undo.flipped {
+            // `apply` was a no-op.
+            return;
+        }
+        match self.kind {
+            MoveKind::ColumnToColumn {
+                src_col, dst_col, ..
+            } => {
+                let (s, d) = (src_col as usize, dst_col as usize);
+                let n = undo.cards_moved as usize;
+                let dst_len = tab.columns[d].len as usize - n;
+                let run = tab.columns[d].cards;
+                tab.columns[d].truncate_to(dst_len as u8);
+                let src = &mut tab.columns[s];
+                if undo.flipped {
+                    src.num_face_down += 1;
+                }
+                let src_len = src.len as usize;
+                src.cards[src_len..src_len + n].copy_from_slice(&run[dst_len..dst_len + n]);
+                src.len += n as u8;
+            }
+
+            MoveKind::ColumnToFoundation { src_col } => {
+                let f = undo.foundation_idx as usize;
+                let Some(card) = foundation_top(tab, f) else {
+                    return;
+                };
+                tab.foundations[f] -= 1;
+                let src = &mut tab.columns[src_col as usize];
+                if undo.flipped {
+                    src.num_face_down += 1;
+                }
+                src.push(card, false);
+            }
+
+            MoveKind::WasteToColumn { dst_col } => {
+                let dst = &mut tab.columns[dst_col as usize];
+                if let Some(card) = dst.top() {
+                    dst.truncate_to(dst.len - 1);
+                    tab.waste.push(card);
+                }
+            }
+
+            MoveKind::WasteToFoundation => {
+                let f = undo.foundation_idx as usize;
+                if let Some(card) = foundation_top(tab, f) {
+                    tab.foundations[f] -= 1;
+                    tab.waste.push(card);
+                }
+            }
+
+            MoveKind::FoundationToColumn {
+                foundation_idx,
+                dst_col,
+            } => {
+                let dst = &mut tab.columns[dst_col as usize];
+                if dst.top().is_some() {
+                    dst.truncate_to(dst.len - 1);
+                    tab.foundations[foundation_idx as usize] += 1;
+                }
+            }
+
+            MoveKind::FlipColumn { col } => {
+                tab.columns[col as usize].num_face_down += 1;
+            }
+
+            // Undoing a deal is a partial redeal and vice versa: move the
+            // same number of cards back one at a time, which restores order.
+            // `apply` draws what the draw rule and stock allow, whatever
+            // `cards_drawn` says, so undo exactly what it recorded.
+            MoveKind::DealFromStock { .. } => {
+                for _ in 0..undo.cards_moved {
+                    if let Some(card) = tab.waste.pop() {
+                        tab.stock.push(card);
+                    }
+                }
+            }
+
+            MoveKind::RedealStock => {
+                while let Some(card) = tab.stock.pop() {
+                    tab.waste.push(card);
+                }
+            }
+        }
     }
 
     /// Render a move as a human-readable string, optionally using details
@@ -532,6 +1105,23 @@ impl Move {
                 }
             }
 
+            MoveKind::FoundationToColumn {
+                foundation_idx,
+                dst_col,
+            } => {
+                let f = foundation_idx as usize;
+                let suit = Suit::ALL[f];
+                match foundation_top(tab, f) {
+                    Some(card) => format!(
+                        "Foundation({:?}): {} -> Column {}",
+                        suit,
+                        card.short_str(),
+                        dst_col + 1
+                    ),
+                    None => format!("Foundation({:?}) (empty) -> Column {}", suit, dst_col + 1),
+                }
+            }
+
             MoveKind::FlipColumn { col } => {
                 let c = col as usize;
                 let col_ref = &tab.columns[c];
@@ -547,12 +1137,135 @@ impl Move {
                 }
             }
 
-            MoveKind::DealFromStock => "Deal from Stock (draw up to 3 cards)".to_string(),
+            MoveKind::DealFromStock { cards_drawn: 1 } => "Deal from Stock (drew 1 card)".to_string(),
+            MoveKind::DealFromStock { cards_drawn } => {
+                format!("Deal from Stock (drew {} cards)", cards_drawn)
+            }
 
             MoveKind::RedealStock => "Redeal Stock from Waste".to_string(),
         }
 
     }
+
+    /// Compact, machine-readable notation for this move. Columns and
+    /// foundations are 1-based:
+    ///
+    /// ```text
+    ///   C3[2]->C5   ColumnToColumn (run starting at src_index 2)
+    ///   C3->F       ColumnToFoundation
+    ///   W->C5       WasteToColumn
+    ///   W->F        WasteToFoundation
+    ///   F1->C5      FoundationToColumn (foundation in `Suit::ALL` order)
+    ///   FC3         FlipColumn
+    ///   D           DealFromStock drawing 3 cards
+    ///   D1, D2      DealFromStock drawing 1 or 2 cards
+    ///   R           RedealStock
+    /// ```
+    ///
+    /// Unlike `describe`, this needs no tableau; `from_notation` parses it
+    /// back.
+    pub fn to_notation(&self) -> String {
+        match self.kind {
+            MoveKind::ColumnToColumn {
+                src_col,
+                src_index,
+                dst_col,
+            } => format!("C{}[{}]->C{}", src_col + 1, src_index, dst_col + 1),
+            MoveKind::ColumnToFoundation { src_col } => format!("C{}->F", src_col + 1),
+            MoveKind::WasteToColumn { dst_col } => format!("W->C{}", dst_col + 1),
+            MoveKind::WasteToFoundation => "W->F".to_string(),
+            MoveKind::FoundationToColumn {
+                foundation_idx,
+                dst_col,
+            } => format!("F{}->C{}", foundation_idx + 1, dst_col + 1),
+            MoveKind::FlipColumn { col } => format!("FC{}", col + 1),
+            MoveKind::DealFromStock { cards_drawn: 3 } => "D".to_string(),
+            MoveKind::DealFromStock { cards_drawn } => format!("D{}", cards_drawn),
+            MoveKind::RedealStock => "R".to_string(),
+        }
+    }
+
+    /// Parse the notation produced by `to_notation` (case-sensitive, no
+    /// whitespace).
+    pub fn from_notation(s: &str) -> Result<Move, MoveParseError> {
+        let kind = match s {
+            "D" => MoveKind::DealFromStock { cards_drawn: 3 },
+            "D1" => MoveKind::DealFromStock { cards_drawn: 1 },
+            "D2" => MoveKind::DealFromStock { cards_drawn: 2 },
+            "R" => MoveKind::RedealStock,
+            "W->F" => MoveKind::WasteToFoundation,
+            _ => {
+                if let Some(col) = s.strip_prefix("FC") {
+                    MoveKind::FlipColumn {
+                        col: parse_notation_index(col, NUM_COLS, || {
+                            MoveParseError::BadColumn(format!("C{}", col))
+                        })?,
+                    }
+                } else if let Some((src, dst)) = s.split_once("->") {
+                    parse_notation_transfer(s, src, dst)?
+                } else {
+                    return Err(MoveParseError::UnknownMove(s.to_string()));
+                }
+            }
+        };
+        Ok(Move { kind })
+    }
+}
+
+/// Parse the `SRC->DST` notation forms (everything except `D`, `R`, `FCn`).
+fn parse_notation_transfer(s: &str, src: &str, dst: &str) -> Result<MoveKind, MoveParseError> {
+    let column = |tok: &str| {
+        let n = tok.strip_prefix('C').ok_or_else(|| MoveParseError::UnknownMove(s.to_string()))?;
+        parse_notation_index(n, NUM_COLS, || MoveParseError::BadColumn(tok.to_string()))
+    };
+
+    if dst == "F" {
+        return match src {
+            "W" => Ok(MoveKind::WasteToFoundation),
+            _ => Ok(MoveKind::ColumnToFoundation { src_col: column(src)? }),
+        };
+    }
+    let dst_col = column(dst)?;
+
+    if src == "W" {
+        Ok(MoveKind::WasteToColumn { dst_col })
+    } else if let Some(f) = src.strip_prefix('F') {
+        let foundation_idx = parse_notation_index(f, NUM_FOUNDATIONS, || {
+            MoveParseError::BadFoundation(src.to_string())
+        })?;
+        Ok(MoveKind::FoundationToColumn {
+            foundation_idx,
+            dst_col,
+        })
+    } else if let Some((col, rest)) = src.split_once('[') {
+        let idx = rest
+            .strip_suffix(']')
+            .ok_or_else(|| MoveParseError::UnknownMove(s.to_string()))?;
+        let src_index = idx
+            .parse::<u8>()
+            .ok()
+            .filter(|&i| (i as usize) < MAX_COL)
+            .ok_or_else(|| MoveParseError::BadIndex(idx.to_string()))?;
+        Ok(MoveKind::ColumnToColumn {
+            src_col: column(col)?,
+            src_index,
+            dst_col,
+        })
+    } else {
+        Err(MoveParseError::UnknownMove(s.to_string()))
+    }
+}
+
+/// Parse a 1-based notation number in `1..=count` into a 0-based index.
+fn parse_notation_index(
+    n: &str,
+    count: usize,
+    err: impl FnOnce() -> MoveParseError,
+) -> Result<u8, MoveParseError> {
+    match n.parse::<u8>() {
+        Ok(v) if v >= 1 && v as usize <= count => Ok(v - 1),
+        _ => Err(err()),
+    }
 }
 
 /// Helper: move a run of cards from `src` to `dst`, where the run begins
@@ -605,7 +1318,7 @@ mod tests {
     use crate::card::Rank;
     use crate::display::print_tableau;
     use crate::game::GameState;
-    use crate::tableau::Tableau;
+    use crate::tableau::{DrawRule, Tableau};
 
     /// Print a hint about how to run these tests to see clean, non-interleaved
     /// human-readable output.
@@ -640,7 +1353,7 @@ mod tests {
     fn random_game_state(seed: u32) -> GameState {
         let mut deck = standard_deck();
         shuffle_deck(&mut deck, seed);
-        GameState::new(deck)
+        GameState::new(deck, DrawRule::Draw3)
     }
 
     /// Demonstration: generate and print legal moves for three different
@@ -685,7 +1398,7 @@ Initial tableau (move stack is empty):");
 
             // After one draw-from-stock: use the real game method `apply_move`
             // to both mutate the tableau and record the move.
-            game.apply_move(Move { kind: MoveKind::DealFromStock });
+            game.apply_move(Move::deal_from_stock(&game.tableau));
             let tab_draw = game.current_tableau();
             println!("
 After one draw-from-stock (move stack has one entry):");
@@ -745,6 +1458,46 @@ After one draw-from-stock (move stack has one entry):");
         tab.foundations[f_idx] = 1; // AH
         assert!(super::can_move_to_foundation(&tab, two_h));
     }
+
+    #[test]
+    fn foundation_to_column_moves_card_back_down() {
+        use crate::card::Suit::*;
+
+        let mut tab = Tableau::new_empty();
+        tab.foundations = [5, 0, 0, 0]; // AH..5H
+        tab.columns[2].push(Card::new(Clubs, Rank::Six), false);
+        tab.columns[4].push(Card::new(Diamonds, Rank::Six), false);
+
+        // Standard rules never offer it; the relaxed generator only offers
+        // the column where 5H fits (black six).
+        assert!(generate_legal_moves(&tab)
+            .iter()
+            .all(|m| !matches!(m.kind, MoveKind::FoundationToColumn { .. })));
+        let mv = Move {
+            kind: MoveKind::FoundationToColumn {
+                foundation_idx: 0,
+                dst_col: 2,
+            },
+        };
+        assert_eq!(generate_foundation_to_column_moves(&tab), vec![mv]);
+        println!("{}", mv.describe(&tab));
+
+        // This partial tableau is not a valid `GameState` (it holds only a
+        // few cards), so check the incremental Zobrist update directly.
+        let zobrist = crate::game::zobrist_table();
+        let mut after = tab;
+        mv.apply(&mut after);
+        assert_eq!(
+            zobrist.update_hash(zobrist.hash_tableau(&tab), &tab, &after, mv),
+            zobrist.hash_tableau(&after)
+        );
+
+        assert_eq!(after.foundations[0], 4);
+        assert_eq!(after.columns[2].len(), 2);
+        assert_eq!(after.columns[2].top(), Some(Card::new(Hearts, Rank::Five)));
+        assert_eq!(after.columns[2].num_face_down(), 0);
+        assert_eq!(after.columns[4].len(), 1);
+    }
     #[test]
     fn moving_last_face_up_card_flips_hidden_column_to_column() {
         use crate::card::{Card, Suit::*, Rank::*};
@@ -838,4 +1591,540 @@ After one draw-from-stock (move stack has one entry):");
             "previously hidden top card should now be face-up",
         );
     }
-}
\ No newline at end of file
+
+    /// Hand-built tableau with foundation moves of two ranks, moves that
+    /// expose hidden cards, and ordinary moves.
+    ///
+    ///   C1: [3S 4S] 5H   (5H -> 6C exposes 4S)
+    ///   C2: 6C
+    ///   C3: [9D] 2C      (2C -> foundation exposes 9D)
+    ///   C4: 7D AD        (AD -> foundation)
+    ///   Waste: AH, Stock: KS, Clubs foundation at Ace.
+    fn ordering_test_tableau() -> Tableau {
+        use crate::card::{Card, Suit::*, Rank::*};
+
+        let mut tab = Tableau::new_empty();
+        tab.columns[0].push(Card::new(Spades, Three), true);
+        tab.columns[0].push(Card::new(Spades, Four), true);
+        tab.columns[0].push(Card::new(Hearts, Five), false);
+        tab.columns[1].push(Card::new(Clubs, Six), false);
+        tab.columns[2].push(Card::new(Diamonds, Nine), true);
+        tab.columns[2].push(Card::new(Clubs, Two), false);
+        tab.columns[3].push(Card::new(Diamonds, Seven), false);
+        tab.columns[3].push(Card::new(Diamonds, Ace), false);
+        tab.waste.push(Card::new(Hearts, Ace));
+        tab.stock.push(Card::new(Spades, King));
+        tab.foundations[super::foundation_index_for(Card::new(Clubs, Ace))] = 1;
+        tab
+    }
+
+    #[test]
+    fn ordered_moves_are_a_permutation_of_legal_moves() {
+        let tab = ordering_test_tableau();
+        let base = generate_legal_moves(&tab);
+
+        let unordered = generate_legal_moves_ordered(&tab, MovePolicy::Unordered);
+        assert_eq!(unordered.as_slice(), base.as_slice());
+
+        for policy in [MovePolicy::FoundationFirst, MovePolicy::ExposeHidden] {
+            let ordered = generate_legal_moves_ordered(&tab, policy);
+            assert_eq!(ordered.len(), base.len());
+            for mv in &base {
+                assert!(ordered.iter().any(|m| m == mv), "{:?} lost {:?}", policy, mv);
+            }
+        }
+    }
+
+    #[test]
+    fn foundation_first_orders_foundation_moves_by_rank() {
+        let tab = ordering_test_tableau();
+        let ordered = generate_legal_moves_ordered(&tab, MovePolicy::FoundationFirst);
+
+        println!("=== moves::foundation_first_orders_foundation_moves_by_rank ===");
+        print_tableau(&tab);
+        for mv in ordered.iter() {
+            println!("  {}", mv.describe(&tab));
+        }
+
+        // Invariant: no foundation move after a non-foundation move, and
+        // foundation moves in non-decreasing rank order.
+        let n_found = ordered.iter().filter(|mv| is_foundation_move(mv)).count();
+        assert_eq!(n_found, 3);
+        assert!(ordered.iter().take(n_found).all(is_foundation_move));
+        let ranks: Vec<u8> = ordered
+            .iter()
+            .take(n_found)
+            .map(|mv| foundation_move_rank(&tab, mv))
+            .collect();
+        assert!(ranks.windows(2).all(|w| w[0] <= w[1]), "ranks {:?}", ranks);
+
+        // AD (column) and AH (waste) both precede 2C.
+        assert_eq!(
+            ordered.as_slice()[2].kind,
+            MoveKind::ColumnToFoundation { src_col: 2 }
+        );
+    }
+
+    #[test]
+    fn expose_hidden_orders_revealing_moves_first() {
+        let tab = ordering_test_tableau();
+        let ordered: Vec<Move> =
+            generate_legal_moves_ordered(&tab, MovePolicy::ExposeHidden).into_iter().collect();
+
+        let n_exposing = ordered.iter().filter(|mv| exposes_hidden_card(&tab, mv)).count();
+        assert_eq!(n_exposing, 2);
+        assert!(ordered[..n_exposing].iter().all(|mv| exposes_hidden_card(&tab, mv)));
+        assert!(ordered[..n_exposing].contains(&Move {
+            kind: MoveKind::ColumnToColumn {
+                src_col: 0,
+                src_index: 2,
+                dst_col: 1,
+            },
+        }));
+
+        // Then the remaining foundation moves, then the rest.
+        let rest = &ordered[n_exposing..];
+        let n_found = rest.iter().filter(|mv| is_foundation_move(mv)).count();
+        assert_eq!(n_found, 2);
+        assert!(rest[..n_found].iter().all(is_foundation_move));
+    }
+
+    /// Under draw-1, every `DealFromStock` moves exactly one card from the
+    /// stock to the waste.
+    #[test]
+    fn draw_one_deals_single_card_to_waste() {
+        let mut game = GameState::new(standard_deck(), DrawRule::Draw1);
+        let stock_before = game.current_tableau().stock.len();
+
+        for dealt in 1..=3u8 {
+            game.apply_move(Move::deal_from_stock(&game.tableau));
+            let tab = game.current_tableau();
+            assert_eq!(tab.waste.len(), dealt, "draw-1 should add one waste card per deal");
+            assert_eq!(tab.stock.len(), stock_before - dealt);
+        }
+
+        // Draw-3 on the same deck turns over three at once, for contrast.
+        let mut game3 = GameState::new(standard_deck(), DrawRule::Draw3);
+        game3.apply_move(Move::deal_from_stock(&game3.tableau));
+        assert_eq!(game3.current_tableau().waste.len(), 3);
+    }
+
+    /// Under draw-1, dealing through the whole stock and redealing restores
+    /// the initial stock order and tableau hash.
+    #[test]
+    fn draw_one_redeal_restores_stock() {
+        let mut game = GameState::new(standard_deck(), DrawRule::Draw1);
+        let initial_tab = game.current_tableau();
+        let initial_hash = game.tableau_hash;
+        let stock_len = initial_tab.stock.len();
+
+        for _ in 0..stock_len {
+            game.apply_move(Move::deal_from_stock(&game.tableau));
+        }
+        let tab = game.current_tableau();
+        assert!(tab.stock.is_empty());
+        assert_eq!(tab.waste.len(), stock_len);
+
+        let moves = generate_legal_moves(&tab);
+        assert!(
+            moves.iter().any(|m| matches!(m.kind, MoveKind::RedealStock)),
+            "redeal should be legal once the stock is exhausted"
+        );
+        assert!(!moves.iter().any(|m| matches!(m.kind, MoveKind::DealFromStock { .. })));
+
+        game.apply_move(Move { kind: MoveKind::RedealStock });
+        let tab = game.current_tableau();
+        assert!(tab.waste.is_empty());
+        assert!(
+            tab.stock.iter().eq(initial_tab.stock.iter()),
+            "redeal should restore the original stock order"
+        );
+        assert_eq!(game.tableau_hash, initial_hash);
+    }
+
+    /// Fuzz: from positions along random games (both draw rules), every
+    /// legal move, including relaxed `FoundationToColumn` moves, must be
+    /// undone exactly by `unapply`.
+    #[test]
+    fn unapply_reverses_every_move_on_random_games() {
+        let mut checked = 0;
+        for seed in 0..60_u32 {
+            let rule = if seed % 2 == 0 { DrawRule::Draw3 } else { DrawRule::Draw1 };
+            let mut deck = standard_deck();
+            shuffle_deck(&mut deck, seed);
+            let mut tab = Tableau::deal_from_shuffled_with_rule(deck, rule);
+            let mut rng = seed;
+            for _ in 0..150 {
+                let mut moves = generate_legal_moves(&tab);
+                moves.extend(generate_foundation_to_column_moves(&tab));
+                if moves.is_empty() {
+                    break;
+                }
+                for mv in &moves {
+                    let mut t = tab;
+                    let undo = mv.apply(&mut t);
+                    mv.unapply(&mut t, undo);
+                    assert_eq!(t, tab, "unapply did not reverse {:?} (seed {})", mv, seed);
+                    checked += 1;
+                }
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                moves[(rng >> 8) as usize % moves.len()].apply(&mut tab);
+            }
+        }
+        println!("checked apply/unapply on {} moves", checked);
+    }
+
+    /// Unapplying a whole line in reverse order returns to the deal.
+    #[test]
+    fn unapply_walks_back_a_line_to_the_deal() {
+        let game = random_game_state(77);
+        let initial = game.tableau;
+        let mut tab = initial;
+        let mut applied = Vec::new();
+        let mut rng: u32 = 5;
+        for _ in 0..80 {
+            let moves = generate_legal_moves(&tab);
+            if moves.is_empty() {
+                break;
+            }
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            let mv = moves[(rng >> 8) as usize % moves.len()];
+            applied.push((mv, mv.apply(&mut tab)));
+        }
+        while let Some((mv, undo)) = applied.pop() {
+            mv.unapply(&mut tab, undo);
+        }
+        assert_eq!(tab, initial);
+    }
+
+    /// Up to `count` positions sampled along seeded random games.
+    fn random_positions(count: usize) -> Vec<Tableau> {
+        let mut positions = Vec::with_capacity(count);
+        let mut seed = 0_u32;
+        while positions.len() < count {
+            let mut deck = standard_deck();
+            shuffle_deck(&mut deck, seed);
+            let mut tab = Tableau::deal_from_shuffled(deck);
+            let mut rng = seed;
+            for _ in 0..100 {
+                positions.push(tab);
+                let moves = generate_legal_moves(&tab);
+                if moves.is_empty() || positions.len() == count {
+                    break;
+                }
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                moves[(rng >> 8) as usize % moves.len()].apply(&mut tab);
+            }
+            seed += 1;
+        }
+        positions
+    }
+
+    #[test]
+    fn generate_into_matches_allocating_version_and_reuses_buffer() {
+        let mut buf = Vec::new();
+        for tab in random_positions(500) {
+            generate_legal_moves_into(&tab, &mut buf);
+            assert_eq!(buf, generate_legal_moves(&tab));
+        }
+
+        // The buffer is cleared, not reallocated.
+        let capacity = buf.capacity();
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+        generate_legal_moves_into(&tab, &mut buf);
+        assert_eq!(buf, generate_legal_moves(&tab));
+        assert_eq!(buf.capacity(), capacity);
+    }
+
+    /// Timing comparison: allocating `generate_legal_moves` vs.
+    /// `generate_legal_moves_into` with one reused buffer, over 10,000
+    /// random positions.
+    ///
+    /// Run with:
+    ///   cargo test --release bench_generate_legal_moves_into -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_generate_legal_moves_into() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        let positions = random_positions(10_000);
+        let rounds = 50;
+
+        let start = Instant::now();
+        let mut total_alloc = 0;
+        for _ in 0..rounds {
+            for tab in &positions {
+                total_alloc += black_box(generate_legal_moves(black_box(tab))).len();
+            }
+        }
+        let alloc_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut total_into = 0;
+        let mut buf = Vec::new();
+        for _ in 0..rounds {
+            for tab in &positions {
+                generate_legal_moves_into(black_box(tab), &mut buf);
+                total_into += black_box(&buf).len();
+            }
+        }
+        let into_time = start.elapsed();
+
+        assert_eq!(total_alloc, total_into);
+        println!(
+            "{} positions x {} rounds: generate_legal_moves {:.2?}, generate_legal_moves_into {:.2?} ({:.2}x)",
+            positions.len(),
+            rounds,
+            alloc_time,
+            into_time,
+            alloc_time.as_secs_f64() / into_time.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn notation_round_trips_every_move_kind() {
+        let cases = [
+            (MoveKind::ColumnToColumn { src_col: 2, src_index: 4, dst_col: 4 }, "C3[4]->C5"),
+            (MoveKind::ColumnToColumn { src_col: 0, src_index: 0, dst_col: 6 }, "C1[0]->C7"),
+            (MoveKind::ColumnToColumn { src_col: 6, src_index: 12, dst_col: 1 }, "C7[12]->C2"),
+            (MoveKind::ColumnToFoundation { src_col: 2 }, "C3->F"),
+            (MoveKind::WasteToColumn { dst_col: 4 }, "W->C5"),
+            (MoveKind::WasteToFoundation, "W->F"),
+            (MoveKind::FoundationToColumn { foundation_idx: 3, dst_col: 0 }, "F4->C1"),
+            (MoveKind::FlipColumn { col: 2 }, "FC3"),
+            (MoveKind::DealFromStock { cards_drawn: 3 }, "D"),
+            (MoveKind::DealFromStock { cards_drawn: 2 }, "D2"),
+            (MoveKind::RedealStock, "R"),
+        ];
+        for (kind, text) in cases {
+            let mv = Move { kind };
+            assert_eq!(mv.to_notation(), text);
+            assert_eq!(Move::from_notation(text), Ok(mv));
+        }
+    }
+
+    /// Every generated move round-trips, including `ColumnToColumn` moves
+    /// of one-card runs and of longer runs (they differ only in `src_index`).
+    #[test]
+    fn notation_round_trips_generated_moves() {
+        let mut saw_single = false;
+        let mut saw_multi = false;
+        for tab in random_positions(500) {
+            for mv in generate_legal_moves(&tab) {
+                assert_eq!(Move::from_notation(&mv.to_notation()), Ok(mv));
+                if let MoveKind::ColumnToColumn { src_col, src_index, .. } = mv.kind {
+                    let run = tab.columns[src_col as usize].len - src_index;
+                    saw_single |= run == 1;
+                    saw_multi |= run > 1;
+                }
+            }
+        }
+        assert!(saw_single && saw_multi);
+    }
+
+    #[test]
+    fn notation_rejects_malformed_strings() {
+        use MoveParseError::*;
+        assert_eq!(Move::from_notation(""), Err(UnknownMove("".to_string())));
+        assert_eq!(Move::from_notation("X"), Err(UnknownMove("X".to_string())));
+        assert_eq!(Move::from_notation("C3->C5"), Err(UnknownMove("C3->C5".to_string())));
+        assert_eq!(Move::from_notation("C8->F"), Err(BadColumn("C8".to_string())));
+        assert_eq!(Move::from_notation("W->C0"), Err(BadColumn("C0".to_string())));
+        assert_eq!(Move::from_notation("FC9"), Err(BadColumn("C9".to_string())));
+        assert_eq!(Move::from_notation("F5->C1"), Err(BadFoundation("F5".to_string())));
+        assert_eq!(Move::from_notation("C1[x]->C2"), Err(BadIndex("x".to_string())));
+        assert_eq!(Move::from_notation("C1[2->C2"), Err(UnknownMove("C1[2->C2".to_string())));
+    }
+
+    #[test]
+    fn prune_symmetric_column_moves_keeps_one_empty_column_king_move() {
+        use crate::card::{Suit::*, Rank::*};
+
+        let mut tab = Tableau::new_empty();
+        // C1: a hidden card under K♠-Q♥, so the run can move to any empty column.
+        tab.columns[0].push(Card::new(Clubs, Two), true);
+        tab.columns[0].push(Card::new(Spades, King), false);
+        tab.columns[0].push(Card::new(Hearts, Queen), false);
+        // C2: K♥ alone; moving it to an empty column only relabels columns.
+        tab.columns[1].push(Card::new(Hearts, King), false);
+        // C4: J♣ fits on Q♥ (a non-empty destination, never pruned).
+        tab.columns[3].push(Card::new(Diamonds, Three), true);
+        tab.columns[3].push(Card::new(Clubs, Jack), false);
+
+        let moves = generate_legal_moves(&tab);
+        let pruned = prune_symmetric_column_moves(&tab, moves.clone());
+
+        println!("=== moves::prune_symmetric_column_moves_keeps_one_empty_column_king_move ===");
+        print_tableau(&tab);
+        println!("Before pruning ({}): {:?}", moves.len(), moves.iter().map(Move::to_notation).collect::<Vec<_>>());
+        println!("After pruning  ({}): {:?}", pruned.len(), pruned.iter().map(Move::to_notation).collect::<Vec<_>>());
+
+        let to_empty = |list: &[Move]| -> Vec<(u8, u8, u8)> {
+            list.iter()
+                .filter_map(|mv| match mv.kind {
+                    MoveKind::ColumnToColumn { src_col, src_index, dst_col }
+                        if tab.columns[dst_col as usize].len == 0 =>
+                    {
+                        Some((src_col, src_index, dst_col))
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        // K♠-Q♥ could go to C3, C5, C6 or C7; K♥ to the same four.
+        assert_eq!(to_empty(&moves).len(), 8);
+        assert_eq!(to_empty(&pruned), vec![(0, 1, 2)]);
+
+        // Moves to non-empty columns (J♣ onto Q♥) and everything else survive.
+        let others = |list: &[Move]| -> Vec<Move> {
+            list.iter()
+                .copied()
+                .filter(|mv| match mv.kind {
+                    MoveKind::ColumnToColumn { dst_col, .. } => tab.columns[dst_col as usize].len != 0,
+                    _ => true,
+                })
+                .collect()
+        };
+        assert_eq!(others(&pruned), others(&moves));
+        assert!(pruned.contains(&Move {
+            kind: MoveKind::ColumnToColumn { src_col: 3, src_index: 1, dst_col: 0 },
+        }));
+    }
+
+    #[test]
+    fn generate_legal_moves_with_limits_drops_exhausted_redeal() {
+        // Empty stock, non-empty waste: RedealStock is legal.
+        let mut tab = Tableau::deal_from_shuffled(standard_deck());
+        while tab.stock.len > 0 {
+            Move::deal_from_stock(&tab).apply(&mut tab);
+        }
+        let is_redeal = |mv: &Move| matches!(mv.kind, MoveKind::RedealStock);
+        let all = generate_legal_moves(&tab);
+        assert!(all.iter().any(is_redeal));
+
+        assert_eq!(generate_legal_moves_with_limits(&tab, 7, None), all);
+        assert_eq!(generate_legal_moves_with_limits(&tab, 1, Some(2)), all);
+        let limited = generate_legal_moves_with_limits(&tab, 2, Some(2));
+        assert!(!limited.iter().any(is_redeal));
+        assert_eq!(limited.len(), all.len() - 1);
+        assert!(!generate_legal_moves_with_limits(&tab, 0, Some(0)).iter().any(is_redeal));
+    }
+
+    #[test]
+    fn sorted_moves_put_foundation_moves_first() {
+        let mk = |kind| Move { kind };
+        let mut moves = vec![
+            mk(MoveKind::RedealStock),
+            mk(MoveKind::ColumnToColumn { src_col: 3, src_index: 2, dst_col: 1 }),
+            mk(MoveKind::WasteToFoundation),
+            mk(MoveKind::DealFromStock { cards_drawn: 3 }),
+            mk(MoveKind::FlipColumn { col: 0 }),
+            mk(MoveKind::ColumnToColumn { src_col: 3, src_index: 1, dst_col: 5 }),
+            mk(MoveKind::WasteToColumn { dst_col: 2 }),
+            mk(MoveKind::ColumnToFoundation { src_col: 6 }),
+            mk(MoveKind::ColumnToFoundation { src_col: 2 }),
+        ];
+        moves.sort();
+        assert_eq!(
+            moves,
+            vec![
+                mk(MoveKind::ColumnToFoundation { src_col: 2 }),
+                mk(MoveKind::ColumnToFoundation { src_col: 6 }),
+                mk(MoveKind::WasteToFoundation),
+                mk(MoveKind::ColumnToColumn { src_col: 3, src_index: 1, dst_col: 5 }),
+                mk(MoveKind::ColumnToColumn { src_col: 3, src_index: 2, dst_col: 1 }),
+                mk(MoveKind::WasteToColumn { dst_col: 2 }),
+                mk(MoveKind::FlipColumn { col: 0 }),
+                mk(MoveKind::DealFromStock { cards_drawn: 3 }),
+                mk(MoveKind::RedealStock),
+            ]
+        );
+
+        // Same on a real position: foundation moves form a prefix.
+        let mut legal = generate_legal_moves(&Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(143)));
+        legal.sort();
+        let first_other = legal.iter().position(|m| !is_foundation_move(m)).unwrap_or(legal.len());
+        assert!(legal[first_other..].iter().all(|m| !is_foundation_move(m)));
+        let unique: std::collections::HashSet<Move> = legal.iter().copied().collect();
+        assert_eq!(unique.len(), legal.len());
+    }
+
+    #[test]
+    fn no_alloc_generation_matches_vec_generation() {
+        let mut buf = [Move { kind: MoveKind::DealFromStock { cards_drawn: 3 } }; 64];
+        let mut positions = 0;
+        let mut most = 0;
+        let mut seed = 0u32;
+        while positions < 1000 {
+            seed += 1;
+            let mut tab = Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(seed));
+            let mut rng: u32 = seed;
+            for _ in 0..50 {
+                let moves = generate_legal_moves(&tab);
+                let n = generate_legal_moves_no_alloc(&tab, &mut buf);
+                assert_eq!(&buf[..n], moves.as_slice());
+                assert!(n <= MAX_LEGAL_MOVES);
+                most = most.max(n);
+                positions += 1;
+                if moves.is_empty() {
+                    break;
+                }
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                moves[(rng >> 8) as usize % moves.len()].apply(&mut tab);
+            }
+        }
+        println!("{} positions, at most {} legal moves", positions, most);
+    }
+
+    /// A draw-3 deal from a stock of 1, 2 or 3 cards records how many it
+    /// actually turns over, and undoing it puts exactly those back.
+    #[test]
+    fn deal_from_short_stock_records_cards_drawn() {
+        for n in 1..=3u8 {
+            let mut tab = Tableau::deal_from_shuffled(standard_deck());
+            while tab.stock.len() > n {
+                tab.stock.pop();
+            }
+            let deal = *generate_legal_moves(&tab)
+                .iter()
+                .find(|m| matches!(m.kind, MoveKind::DealFromStock { .. }))
+                .unwrap();
+            assert_eq!(deal.kind, MoveKind::DealFromStock { cards_drawn: n });
+            assert_eq!(deal, Move::deal_from_stock(&tab));
+            println!("{}  [{}]", deal.describe(&tab), deal.to_notation());
+            let plural = if n == 1 { "card" } else { "cards" };
+            assert_eq!(deal.describe(&tab), format!("Deal from Stock (drew {} {})", n, plural));
+            assert_eq!(Move::from_notation(&deal.to_notation()), Ok(deal));
+
+            let before = tab;
+            let undo = deal.apply(&mut tab);
+            assert_eq!(undo.cards_moved, n);
+            assert!(tab.stock.is_empty());
+            assert_eq!(tab.waste.len(), n);
+            deal.unapply(&mut tab, undo);
+            assert!(tab == before);
+        }
+
+        // A bare `D` read from notation is fixed up to the real count.
+        let mut tab = Tableau::deal_from_shuffled(standard_deck());
+        while tab.stock.len() > 2 {
+            tab.stock.pop();
+        }
+        let parsed = Move::from_notation("D").unwrap();
+        assert_eq!(parsed.kind, MoveKind::DealFromStock { cards_drawn: 3 });
+        assert_eq!(parsed.with_deal_count_for(&tab), Move::deal_from_stock(&tab));
+        let mut game = GameState::new(standard_deck(), DrawRule::Draw1);
+        game.apply_move(parsed);
+        assert_eq!(game.moves[0].kind, MoveKind::DealFromStock { cards_drawn: 1 });
+
+        // Applied as-is on draw-1, the bare `D` still undoes cleanly.
+        let mut tab = Tableau::deal_from_shuffled(standard_deck());
+        tab.draw_rule = DrawRule::Draw1;
+        let before = tab;
+        let undo = parsed.apply(&mut tab);
+        assert_eq!(undo.cards_moved, 1);
+        parsed.unapply(&mut tab, undo);
+        assert!(tab == before);
+    }
+}
@@ -0,0 +1,178 @@
+//! Error types for the text formats used by this crate.
+//!
+//! Parsers for these formats live next to the types they produce (e.g.
+//! `Tableau::from_compact_str` in `tableau.rs`, `Card`'s `FromStr` in
+//! `card.rs`, `Move::from_notation` in `moves.rs`); this module only holds the error types so callers can match
+//! on what went wrong.
+
+use core::fmt;
+use std::io;
+
+/// Why a compact text string could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A `KEY:value` section was expected but not found.
+    MissingSection(String),
+    /// A section key was not recognised, or appeared more than once.
+    UnexpectedSection(String),
+    /// A section was not of the form `KEY:value`.
+    MalformedSection(String),
+    /// A card string was not a valid short card like "AH" or "td".
+    BadCard(String),
+    /// A foundation entry was not a rank character (or `0`) plus the
+    /// expected suit character.
+    BadFoundation(String),
+    /// The draw rule was not `1` or `3`.
+    BadDrawRule(String),
+    /// A face-down card appeared above a face-up card in a column.
+    FaceDownAboveFaceUp { column: usize },
+    /// A pile or column holds more cards than it has room for.
+    TooManyCards {
+        section: String,
+        count: usize,
+        capacity: usize,
+    },
+    /// The tableau does not hold exactly one full deck.
+    WrongCardCount(usize),
+    /// The same card appears more than once.
+    DuplicateCard(String),
+    /// The move at this (0-based) index of a move list could not be parsed
+    /// or is not legal in the position it is played from.
+    BadMove { index: usize, notation: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingSection(key) => write!(f, "missing section '{}'", key),
+            ParseError::UnexpectedSection(key) => {
+                write!(f, "unexpected or repeated section '{}'", key)
+            }
+            ParseError::MalformedSection(s) => {
+                write!(f, "malformed section '{}' (expected KEY:value)", s)
+            }
+            ParseError::BadCard(s) => write!(f, "invalid card '{}'", s),
+            ParseError::BadFoundation(s) => write!(f, "invalid foundation entry '{}'", s),
+            ParseError::BadDrawRule(s) => write!(f, "invalid draw rule '{}' (expected 1 or 3)", s),
+            ParseError::FaceDownAboveFaceUp { column } => write!(
+                f,
+                "column {} has a face-down card above a face-up card",
+                column + 1
+            ),
+            ParseError::TooManyCards {
+                section,
+                count,
+                capacity,
+            } => write!(
+                f,
+                "section '{}' has {} cards, capacity is {}",
+                section, count, capacity
+            ),
+            ParseError::WrongCardCount(n) => write!(f, "tableau holds {} cards, expected 52", n),
+            ParseError::DuplicateCard(s) => write!(f, "card '{}' appears more than once", s),
+            ParseError::BadMove { index, notation } => {
+                write!(f, "move {} '{}' is not a legal move there", index + 1, notation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why a short card string like "AH" could not be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardParseError {
+    /// The string was not exactly two characters (rank then suit).
+    InvalidLength,
+    /// The first character was not a rank (`A`, `2`..`9`, `T`, `J`, `Q`, `K`).
+    UnknownRank(char),
+    /// The second character was not a suit (`H`, `C`, `S`, `D` or a Unicode
+    /// suit symbol).
+    UnknownSuit(char),
+}
+
+impl fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardParseError::InvalidLength => {
+                write!(f, "expected a rank and a suit character, like 'AH'")
+            }
+            CardParseError::UnknownRank(c) => write!(f, "unknown rank '{}'", c),
+            CardParseError::UnknownSuit(c) => write!(f, "unknown suit '{}'", c),
+        }
+    }
+}
+
+impl std::error::Error for CardParseError {}
+
+/// Why a move notation string (see `Move::to_notation`) could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoveParseError {
+    /// The string is not any of the notation forms.
+    UnknownMove(String),
+    /// A column was not `C1`..`C7`.
+    BadColumn(String),
+    /// A foundation was not `F1`..`F4`.
+    BadFoundation(String),
+    /// A run start index (`[n]`) was not a valid position in a column.
+    BadIndex(String),
+}
+
+impl fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveParseError::UnknownMove(s) => write!(f, "unrecognised move '{}'", s),
+            MoveParseError::BadColumn(s) => write!(f, "invalid column '{}' (expected C1..C7)", s),
+            MoveParseError::BadFoundation(s) => {
+                write!(f, "invalid foundation '{}' (expected F1..F4)", s)
+            }
+            MoveParseError::BadIndex(s) => write!(f, "invalid run start index '{}'", s),
+        }
+    }
+}
+
+impl std::error::Error for MoveParseError {}
+
+/// Why a saved game file (see `GameState::import_from_json_file`) could not
+/// be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read or written.
+    IoError(io::Error),
+    /// The file is not valid JSON of the expected shape, or a card string
+    /// in it is not a valid short card.
+    ParseError(String),
+    /// Move number `index` (0-based) is not valid notation, or is not a
+    /// legal move at that point in the game.
+    InvalidMove { index: usize, notation: String },
+    /// The saved deck does not hold exactly one full deck.
+    WrongCardCount(usize),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::IoError(e) => write!(f, "I/O error: {}", e),
+            LoadError::ParseError(s) => write!(f, "parse error: {}", s),
+            LoadError::InvalidMove { index, notation } => {
+                write!(f, "move {} ('{}') is not valid here", index + 1, notation)
+            }
+            LoadError::WrongCardCount(n) => write!(f, "deck holds {} cards, expected 52", n),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::IoError(e)
+    }
+}
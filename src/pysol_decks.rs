@@ -9,13 +9,25 @@
 //! whether they come from CLI flags or from a text file that contains one or
 //! more dumped decks.
 
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
 use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader};
+use std::ops::RangeInclusive;
 use std::path::Path;
 
+use rayon::prelude::*;
+
 use crate::card::{Card, CARDS_PER_DECK};
+use crate::game::layout_from_imported_deck;
+use crate::tableau::Tableau;
 
 const DECK_LEN: usize = CARDS_PER_DECK as usize;
 
+/// How many bytes before a deck's opening `[` are searched for its label.
+const LABEL_LOOKBACK: usize = 512;
+
 #[derive(Clone, Debug)]
 pub struct DeckSpec {
     /// Human-readable label (seed, filename+index, etc.)
@@ -24,6 +36,52 @@ pub struct DeckSpec {
     pub deck: [Card; DECK_LEN],
 }
 
+/// Two specs are equal when they hold the same deck permutation; the
+/// `label` is ignored, so the same deal loaded from two sources compares
+/// equal.
+impl PartialEq for DeckSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.deck == other.deck
+    }
+}
+
+impl Eq for DeckSpec {}
+
+impl Hash for DeckSpec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deck.hash(state);
+    }
+}
+
+impl DeckSpec {
+    /// Format the deck as a bracketed index list (`[i, j, k, ...]`), the
+    /// inverse of `parse_bracketed_deck_list`.
+    pub fn to_pysol_bracketed_string(&self) -> String {
+        let parts: Vec<String> = self.deck.iter().map(|c| c.index().to_string()).collect();
+        format!("[{}]", parts.join(", "))
+    }
+
+    /// Format the deck the way `dump_pysolfc_deal.py` prints it: a header
+    /// line naming the game, then the bracketed index list:
+    ///
+    /// ```text
+    /// PySolFC shuffled talon for game 24:
+    /// [51, 32, 3, ...]
+    /// ```
+    ///
+    /// A `seed:` label prefix is dropped from the header. Like the dump
+    /// script, the indices are already in `Card::index` space, so
+    /// `extract_decks_from_text` reads the same deck back.
+    pub fn to_canonical_string(&self) -> String {
+        let game = self.label.strip_prefix("seed:").unwrap_or(&self.label);
+        format!(
+            "PySolFC shuffled talon for game {}:\n{}\n",
+            game,
+            self.to_pysol_bracketed_string()
+        )
+    }
+}
+
 /// Parse a single bracketed integer list (e.g. "[1, 2, 3]") into a deck.
 ///
 /// The list must contain exactly 52 integers, each in 0..=51, with no duplicates.
@@ -75,6 +133,58 @@ pub fn parse_bracketed_deck_list(s: &str) -> Result<[Card; DECK_LEN], String> {
     Ok(deck)
 }
 
+/// Remove repeated deck permutations, keeping the first occurrence (and its
+/// label) and preserving order.
+pub fn dedup_decks(specs: &mut Vec<DeckSpec>) {
+    let mut seen: HashSet<[Card; DECK_LEN]> = HashSet::new();
+    specs.retain(|spec| seen.insert(spec.deck));
+}
+
+/// Why `validate_deck_layout` rejected the deal of a deck.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The dealt tableau does not hold 52 cards (`Tableau::total_cards`).
+    WrongCardCount(u8),
+    /// This (0-based) column is empty or has no face-up card on top.
+    ColumnInvariantViolated(usize),
+    /// Cards ended up on the waste, which a fresh deal never does.
+    NonEmptyWasteAfterDeal,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::WrongCardCount(n) => write!(f, "dealt tableau holds {} cards, expected 52", n),
+            LayoutError::ColumnInvariantViolated(c) => {
+                write!(f, "column {} has no face-up top card after the deal", c + 1)
+            }
+            LayoutError::NonEmptyWasteAfterDeal => write!(f, "waste is not empty after the deal"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Deal `spec` (through `game::layout_from_imported_deck`, the same path the
+/// CLI uses) and sanity-check the result: 52 cards in total, every column
+/// ends in a face-up card (`num_face_down < len`), and the waste is empty.
+///
+/// Returns the dealt tableau, so callers can validate and deal in one step.
+pub fn validate_deck_layout(spec: &DeckSpec) -> Result<Tableau, LayoutError> {
+    let tab = layout_from_imported_deck(spec.deck);
+    let total = tab.total_cards();
+    if total != CARDS_PER_DECK {
+        return Err(LayoutError::WrongCardCount(total));
+    }
+    if let Some(c) = tab.columns.iter().position(|col| col.num_face_down >= col.len) {
+        return Err(LayoutError::ColumnInvariantViolated(c));
+    }
+    if !tab.waste.is_empty() {
+        return Err(LayoutError::NonEmptyWasteAfterDeal);
+    }
+    Ok(tab)
+}
+
 fn is_deck_chars_only(s: &str) -> bool {
     // Very tolerant: allow digits, commas, whitespace, brackets, and line breaks.
     s.chars().all(|c| {
@@ -141,18 +251,18 @@ pub fn extract_decks_from_text(text: &str, default_label: &str) -> Vec<DeckSpec>
         }
 
         let candidate = &text[i..=j];
-        if is_deck_chars_only(candidate) {
-            if let Ok(deck) = parse_bracketed_deck_list(candidate) {
-                // Look back a little bit for a label.
-                let lookback_start = i.saturating_sub(512);
-                let nearby = &text[lookback_start..i];
-                let label = sniff_label_near(nearby).unwrap_or_else(|| {
-                    deck_index += 1;
-                    format!("{}#{}", default_label, deck_index)
-                });
-
-                out.push(DeckSpec { label, deck });
-            }
+        if is_deck_chars_only(candidate)
+            && let Ok(deck) = parse_bracketed_deck_list(candidate)
+        {
+            // Look back a little bit for a label.
+            let lookback_start = i.saturating_sub(LABEL_LOOKBACK);
+            let nearby = &text[lookback_start..i];
+            let label = sniff_label_near(nearby).unwrap_or_else(|| {
+                deck_index += 1;
+                format!("{}#{}", default_label, deck_index)
+            });
+
+            out.push(DeckSpec { label, deck });
         }
 
         i = j + 1;
@@ -161,19 +271,84 @@ pub fn extract_decks_from_text(text: &str, default_label: &str) -> Vec<DeckSpec>
     out
 }
 
+/// Streaming version of `extract_decks_from_text`: reads `reader` a line
+/// at a time and finds the same decks, with the same labels, without
+/// holding the whole input in memory.
+///
+/// A `[...]` span may run over several lines; it is buffered until its
+/// `]` arrives. Label sniffing sees the last `LABEL_LOOKBACK` bytes before
+/// each `[`, kept in a ring buffer. A read error ends the scan and returns
+/// the decks found so far (`load_decks_from_file` reports it instead).
+pub fn extract_decks_from_reader(reader: impl BufRead, default_label: &str) -> Vec<DeckSpec> {
+    let mut out = Vec::new();
+    let _ = scan_decks_from_reader(reader, default_label, &mut out);
+    out
+}
+
+fn scan_decks_from_reader(
+    mut reader: impl BufRead,
+    default_label: &str,
+    out: &mut Vec<DeckSpec>,
+) -> io::Result<()> {
+    let mut deck_index = 0usize;
+    let mut line: Vec<u8> = Vec::new();
+    // The last `LABEL_LOOKBACK` bytes read, oldest first.
+    let mut context: VecDeque<u8> = VecDeque::with_capacity(LABEL_LOOKBACK);
+    // The open `[...]` span, with the context as it was at its `[`.
+    let mut span: Option<(Vec<u8>, String)> = None;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            // An unclosed span at EOF is dropped, as in the text version.
+            return Ok(());
+        }
+        for &b in &line {
+            if let Some((candidate, nearby)) = &mut span {
+                candidate.push(b);
+                if b == b']' {
+                    let candidate = String::from_utf8_lossy(candidate);
+                    if is_deck_chars_only(&candidate)
+                        && let Ok(deck) = parse_bracketed_deck_list(&candidate)
+                    {
+                        let label = sniff_label_near(nearby).unwrap_or_else(|| {
+                            deck_index += 1;
+                            format!("{}#{}", default_label, deck_index)
+                        });
+                        out.push(DeckSpec { label, deck });
+                    }
+                    span = None;
+                }
+            } else if b == b'[' {
+                let nearby = String::from_utf8_lossy(context.make_contiguous()).into_owned();
+                span = Some((vec![b], nearby));
+            }
+
+            if context.len() == LABEL_LOOKBACK {
+                context.pop_front();
+            }
+            context.push_back(b);
+        }
+    }
+}
+
 /// Load decks from a text file containing one or more dumped PySol decks.
 ///
-/// Returns an empty Vec if no deck lists were found.
+/// The file is streamed through `extract_decks_from_reader`, so large
+/// dumps are never read into memory whole. Returns an empty Vec if no
+/// deck lists were found.
 pub fn load_decks_from_file(path: &Path) -> Result<Vec<DeckSpec>, String> {
-    let text = fs::read_to_string(path)
-        .map_err(|e| format!("could not read deck file '{}': {}", path.display(), e))?;
+    let read_err = |e: io::Error| format!("could not read deck file '{}': {}", path.display(), e);
+    let file = fs::File::open(path).map_err(read_err)?;
 
     let default_label = path
         .file_name()
         .map(|s| s.to_string_lossy().to_string())
         .unwrap_or_else(|| "pysol_file".to_string());
 
-    Ok(extract_decks_from_text(&text, &default_label))
+    let mut out = Vec::new();
+    scan_decks_from_reader(BufReader::new(file), &default_label, &mut out).map_err(read_err)?;
+    Ok(out)
 }
 
 // -----------------------------------------------------------------------------
@@ -213,30 +388,97 @@ pub fn load_seeds_from_file(path: &Path) -> Result<Vec<DeckSpec>, String> {
 
     let mut out: Vec<DeckSpec> = Vec::new();
     for (lineno, line) in text.lines().enumerate() {
-        let mut s = line;
-        if let Some(p) = s.find('#') {
-            s = &s[..p];
-        }
-        let s = s.trim();
+        let s = strip_seed_line(line);
         if s.is_empty() {
             continue;
         }
         match deck_from_pysol_seed_str(s) {
             Ok(spec) => out.push(spec),
-            Err(e) => {
-                return Err(format!(
-                    "could not parse seed on line {} of '{}': {} (line was {:?})",
-                    lineno + 1,
-                    path.display(),
-                    e,
-                    line
-                ))
-            }
+            Err(e) => return Err(seed_line_error(path, lineno + 1, &e, line)),
         }
     }
     Ok(out)
 }
 
+/// Like `load_seeds_from_file`, but never stops at a bad line: returns the
+/// decks for every seed that parsed plus one error message per line that
+/// did not (in file order).
+///
+/// With `line_range` only those lines (1-based, inclusive) are read, e.g.
+/// `Some(100..=200)` for lines 100 to 200 of a large file. Failing to read
+/// the file at all is reported as the single error.
+pub fn load_seeds_from_file_tolerant(
+    path: &Path,
+    line_range: Option<RangeInclusive<usize>>,
+) -> (Vec<DeckSpec>, Vec<String>) {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            return (
+                Vec::new(),
+                vec![format!("could not read seed file '{}': {}", path.display(), e)],
+            )
+        }
+    };
+
+    let mut specs: Vec<DeckSpec> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for (lineno, line) in (1..).zip(text.lines()) {
+        if line_range.as_ref().is_some_and(|r| !r.contains(&lineno)) {
+            continue;
+        }
+        let s = strip_seed_line(line);
+        if s.is_empty() {
+            continue;
+        }
+        match deck_from_pysol_seed_str(s) {
+            Ok(spec) => specs.push(spec),
+            Err(e) => errors.push(seed_line_error(path, lineno, &e, line)),
+        }
+    }
+    (specs, errors)
+}
+
+/// A seed file line with any `#` comment removed and whitespace trimmed.
+fn strip_seed_line(line: &str) -> &str {
+    match line.find('#') {
+        Some(p) => line[..p].trim(),
+        None => line.trim(),
+    }
+}
+
+fn seed_line_error(path: &Path, lineno: usize, err: &str, line: &str) -> String {
+    format!(
+        "could not parse seed on line {} of '{}': {} (line was {:?})",
+        lineno,
+        path.display(),
+        err,
+        line
+    )
+}
+
+/// Generate decks for every PySolFC game number in `start..=end`, in order.
+///
+/// One entry per seed: a seed that cannot be turned into a deck gives an
+/// `Err` with a message naming it instead of aborting the whole range.
+/// An empty range (`start > end`) gives an empty `Vec`.
+pub fn generate_seed_range(start: u64, end: u64) -> Vec<Result<DeckSpec, String>> {
+    (start..=end).map(deck_from_pysol_seed_u64).collect()
+}
+
+/// `generate_seed_range` on rayon's thread pool; the result is in the same
+/// (seed) order.
+pub fn generate_seed_range_parallel(start: u64, end: u64) -> Vec<Result<DeckSpec, String>> {
+    (start..=end)
+        .into_par_iter()
+        .map(deck_from_pysol_seed_u64)
+        .collect()
+}
+
+fn deck_from_pysol_seed_u64(seed: u64) -> Result<DeckSpec, String> {
+    deck_from_pysol_seed_str(&seed.to_string()).map_err(|e| format!("seed {}: {}", seed, e))
+}
+
 // --- PySolFC card construction + suit mapping to klondike_chat::Card::index() ---
 
 // PySolFC suit order: Clubs(0), Spades(1), Hearts(2), Diamonds(3)
@@ -326,7 +568,7 @@ fn generate_deck_from_seed(seed: &PysolSeed) -> Result<[Card; DECK_LEN], String>
             let threshold = BigUint::from(32000u32);
             if n < &threshold {
                 // Safe because n < 32000.
-                let n_u64 = n.to_u64_digits().get(0).copied().unwrap_or(0);
+                let n_u64 = n.to_u64_digits().first().copied().unwrap_or(0);
                 let mut rng = LCRandom31::new(n_u64)?;
                 rng.shuffle(&mut cards);
             } else {
@@ -348,9 +590,15 @@ fn generate_deck_from_seed(seed: &PysolSeed) -> Result<[Card; DECK_LEN], String>
 
 // --- RandomBase.shuffle equivalent (Fisher–Yates) for our RNGs ---
 
-trait Shuffle {
+/// PySolFC's `RandomBase.randint` / `RandomBase.shuffle`, implemented by
+/// `LCRandom31` and `MTRandom`.
+pub trait Shuffle {
+    /// Uniform integer in `a..=b`, the way the matching PySolFC RNG's
+    /// `randint(a, b)` computes it.
     fn randint_inclusive(&mut self, a: usize, b: usize) -> usize;
 
+    /// Fisher–Yates from the end of `seq`, swapping `seq[n]` with
+    /// `seq[randint(0, n)]` for `n = len-1` down to 1.
     fn shuffle<T>(&mut self, seq: &mut [T]) {
         if seq.len() <= 1 {
             return;
@@ -364,7 +612,9 @@ trait Shuffle {
 
 // --- LCRandom31 (matches pysol_cards.random.LCRandom31) ---
 
-struct LCRandom31 {
+/// The Microsoft C `rand()` style LCG PySolFC uses for game numbers below
+/// 32000 and for `msNNNN` deals (`pysol_cards.random.LCRandom31`).
+pub struct LCRandom31 {
     seed: u64,
     seedx: u64,
 }
@@ -372,8 +622,9 @@ struct LCRandom31 {
 impl LCRandom31 {
     const MAX_SEED: u64 = (1u64 << 33) - 1;
 
-    fn new(seed: u64) -> Result<Self, String> {
-        if seed < 1 || seed > Self::MAX_SEED {
+    /// Seeded generator; `seed` must be in `1..=2^33 - 1`.
+    pub fn new(seed: u64) -> Result<Self, String> {
+        if !(1..=Self::MAX_SEED).contains(&seed) {
             return Err("ms seed out of range".to_string());
         }
         let seedx = if seed < 0x1_0000_0000 {
@@ -398,8 +649,9 @@ impl LCRandom31 {
         ((self.seedx >> 16) & 0xffff) as u16
     }
 
-    /// Mirrors LCRandom31.random() from pysol_cards.
-    fn random_u16ish(&mut self) -> u32 {
+    /// Mirrors LCRandom31.random() from pysol_cards. For seeds below
+    /// 2^31 this is MSVC `rand()`: 15 bits per call.
+    pub fn random_u16ish(&mut self) -> u32 {
         if self.seed < 0x1_0000_0000 {
             let r = self.rand_15() as u32;
             if self.seed < 0x8000_0000 {
@@ -426,14 +678,21 @@ impl Shuffle for LCRandom31 {
 
 // --- MT19937 matching CPython's _random (used by random.Random.random()) ---
 
-struct MTRandom {
+/// MT19937 seeded and sampled like CPython's `random.Random`, which
+/// PySolFC uses for game numbers of 32000 and above.
+pub struct MTRandom {
     mt: [u32; 624],
     index: usize,
 }
 
 impl MTRandom {
+    /// `random.Random(seed)`.
+    pub fn new(seed: u64) -> Self {
+        Self::new_big(&BigUint::from(seed))
+    }
 
-    fn new_big(seed: &BigUint) -> Self {
+    /// `random.Random(seed)` for an arbitrarily large non-negative seed.
+    pub fn new_big(seed: &BigUint) -> Self {
         let mut r = Self {
             mt: [0u32; 624],
             index: 624,
@@ -450,7 +709,7 @@ impl MTRandom {
             return;
         }
         let bytes = seed.to_bytes_le();
-        let mut key: Vec<u32> = Vec::with_capacity((bytes.len() + 3) / 4);
+        let mut key: Vec<u32> = Vec::with_capacity(bytes.len().div_ceil(4));
         for chunk in bytes.chunks(4) {
             let mut buf = [0u8; 4];
             buf[..chunk.len()].copy_from_slice(chunk);
@@ -540,7 +799,8 @@ impl MTRandom {
         self.index = 0;
     }
 
-    fn gen_u32(&mut self) -> u32 {
+    /// Next raw 32-bit output (`random.Random.getrandbits(32)`).
+    pub fn gen_u32(&mut self) -> u32 {
         if self.index >= 624 {
             self.twist();
         }
@@ -555,7 +815,8 @@ impl MTRandom {
         y
     }
 
-    fn random_f64(&mut self) -> f64 {
+    /// `random.Random.random()`: a float in `[0, 1)` with 53 random bits.
+    pub fn random_f64(&mut self) -> f64 {
         // CPython random():
         //   a = genrand_uint32() >> 5  (27 bits)
         //   b = genrand_uint32() >> 6  (26 bits)
@@ -578,3 +839,213 @@ impl Shuffle for MTRandom {
         a + ((r * (span as f64)) as usize)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_range_1_to_10_gives_labelled_decks_in_order() {
+        let specs: Vec<DeckSpec> = generate_seed_range(1, 10)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(specs.len(), 10);
+        for (spec, seed) in specs.iter().zip(1..) {
+            assert!(spec.label.starts_with("seed:"), "label {}", spec.label);
+            let single = deck_from_pysol_seed_str(&seed.to_string()).unwrap();
+            assert_eq!(spec.label, single.label);
+            assert_eq!(spec.deck, single.deck);
+        }
+
+        let parallel = generate_seed_range_parallel(1, 10);
+        assert_eq!(parallel.len(), 10);
+        for (p, s) in parallel.iter().zip(&specs) {
+            let p = p.as_ref().unwrap();
+            assert_eq!((&p.label, p.deck), (&s.label, s.deck));
+        }
+
+        assert!(generate_seed_range(5, 4).is_empty());
+    }
+
+    #[test]
+    fn bracketed_list_round_trips_through_layout_and_export() {
+        for seed in ["1", "24", "11982", "ms3"] {
+            let spec = deck_from_pysol_seed_str(seed).unwrap();
+            let text = spec.to_pysol_bracketed_string();
+            assert!(text.starts_with("[") && text.ends_with("]"), "{}", text);
+
+            let deck = parse_bracketed_deck_list(&text).unwrap();
+            assert_eq!(deck, spec.deck);
+
+            let mut indices = [0u8; DECK_LEN];
+            for (i, c) in deck.iter().enumerate() {
+                indices[i] = c.index();
+            }
+            let tab = crate::game::layout_from_imported_deck_indices(indices);
+            assert_eq!(tab.export_pysol_indices(), indices, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn known_pysol_deck_passes_layout_validation() {
+        let spec = deck_from_pysol_seed_str("24").unwrap();
+        let tab = validate_deck_layout(&spec).unwrap();
+        assert_eq!(tab.pile_heights(), [1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(tab.stock.len(), 24);
+        assert_eq!(
+            LayoutError::ColumnInvariantViolated(2).to_string(),
+            "column 3 has no face-up top card after the deal"
+        );
+    }
+
+    #[test]
+    fn canonical_string_round_trips_through_extract() {
+        let specs: Vec<DeckSpec> = ["1", "24", "ms3"]
+            .iter()
+            .map(|seed| deck_from_pysol_seed_str(seed).unwrap())
+            .collect();
+        let text: String = specs.iter().map(DeckSpec::to_canonical_string).collect();
+        println!("{}", text);
+        assert!(text.starts_with("PySolFC shuffled talon for game 1:\n["));
+
+        let back = extract_decks_from_text(&text, "dump");
+        assert_eq!(back.len(), specs.len());
+        for (a, b) in specs.iter().zip(&back) {
+            assert_eq!(a.deck, b.deck);
+        }
+        assert_eq!(back[1].label, "24");
+    }
+
+    #[test]
+    fn dedup_decks_keeps_first_of_each_permutation() {
+        let one = deck_from_pysol_seed_str("1").unwrap();
+        let two = deck_from_pysol_seed_str("2").unwrap();
+        let one_again = DeckSpec {
+            label: "file:1".to_string(),
+            deck: parse_bracketed_deck_list(&one.to_pysol_bracketed_string()).unwrap(),
+        };
+        assert_eq!(one, one_again, "labels are ignored");
+        assert_ne!(one, two);
+
+        let mut specs = vec![one.clone(), two.clone(), one_again];
+        dedup_decks(&mut specs);
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].label, one.label);
+        assert_eq!(specs[1].label, two.label);
+    }
+
+    #[test]
+    fn tolerant_seed_loader_keeps_good_lines_and_reports_bad_ones() {
+        let path = std::env::temp_dir().join(format!("klondike_seeds_{}.txt", std::process::id()));
+        fs::write(&path, "# seeds\n1\nnot a seed!\n2 # trailing comment\n\n???\n3\n").unwrap();
+
+        let (specs, errors) = load_seeds_from_file_tolerant(&path, None);
+        for e in &errors {
+            println!("{}", e);
+        }
+        assert_eq!(specs.len(), 3);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("line 3"), "{}", errors[0]);
+        assert!(errors[1].contains("line 6"), "{}", errors[1]);
+        assert!(load_seeds_from_file(&path).is_err(), "the strict loader still aborts");
+
+        // Lines 2..=4: seed 1, one bad line, seed 2.
+        let (specs, errors) = load_seeds_from_file_tolerant(&path, Some(2..=4));
+        assert_eq!(specs.iter().map(|s| s.label.as_str()).collect::<Vec<_>>(), ["seed:1", "seed:2"]);
+        assert_eq!(errors.len(), 1);
+
+        fs::remove_file(&path).unwrap();
+        let (specs, errors) = load_seeds_from_file_tolerant(&path, None);
+        assert!(specs.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+
+    /// Reference vectors for the PySolFC RNGs and seed shuffles.
+    ///
+    /// `dump_pysolfc_deal.py` is not part of this repo, so these were not
+    /// taken from it. The MT19937 values come from CPython 3.11.7's
+    /// `random.Random(32000)`. The LCRandom31 values are the well-known
+    /// MSVC `rand()` sequence for `srand(1)`. The decks are this
+    /// module's suit-mapped dealing order (the bracketed lists that
+    /// `dump_pysolfc_deal.py` prints). They came from a short Python port of
+    /// `RandomBase.shuffle` driven by CPython's `random.Random` (seeds
+    /// >= 32000) or the LCG above (seeds < 32000).
+    #[test]
+    fn pysol_rng_and_shuffle_test_vectors() {
+        let mut mt = MTRandom::new(32000);
+        assert_eq!([mt.gen_u32(), mt.gen_u32(), mt.gen_u32()], [1598467916, 3084624151, 3613492840]);
+        let mut mt = MTRandom::new(32000);
+        assert_eq!(mt.random_f64(), 0.3721723162993418);
+        assert_eq!(mt.random_f64(), 0.8413318621630471);
+
+        let mut lc = LCRandom31::new(1).unwrap();
+        let first: Vec<u32> = (0..10).map(|_| lc.random_u16ish()).collect();
+        assert_eq!(first, [41, 18467, 6334, 26500, 19169, 15724, 11478, 29358, 26962, 24464]);
+        assert!(LCRandom31::new(0).is_err());
+
+        let vectors: [(&str, [u8; DECK_LEN]); 4] = [
+            ("1", [
+                41, 18, 8, 40, 30, 0, 37, 31, 49, 48, 9, 32, 14, 44, 50, 23, 20, 51, 7, 45, 43, 16, 15, 21, 25, 29,
+                39, 46, 27, 13, 26, 1, 24, 11, 28, 12, 4, 17, 42, 38, 34, 5, 3, 47, 33, 22, 2, 10, 36, 6, 19, 35,
+            ]),
+            ("19999", [
+                0, 5, 49, 50, 26, 38, 24, 43, 10, 13, 4, 35, 3, 12, 8, 46, 30, 25, 2, 48, 27, 47, 21, 51, 37, 29,
+                45, 44, 7, 11, 42, 9, 36, 39, 31, 33, 22, 6, 15, 17, 18, 41, 20, 32, 14, 19, 23, 40, 1, 16, 28, 34,
+            ]),
+            ("32000", [
+                32, 42, 36, 46, 44, 2, 41, 9, 23, 15, 14, 30, 47, 13, 21, 27, 3, 31, 33, 34, 35, 8, 51, 50, 5, 45,
+                4, 37, 39, 11, 22, 16, 0, 49, 29, 10, 48, 28, 6, 20, 18, 1, 40, 43, 38, 24, 12, 17, 19, 7, 25, 26,
+            ]),
+            ("32001", [
+                16, 13, 49, 7, 30, 31, 44, 12, 23, 29, 6, 22, 10, 40, 41, 36, 32, 1, 48, 33, 21, 26, 15, 3, 50, 35,
+                24, 14, 9, 46, 27, 0, 47, 51, 34, 17, 11, 43, 4, 37, 18, 45, 39, 5, 2, 38, 42, 25, 28, 20, 8, 19,
+            ]),
+        ];
+        for (seed, expected) in vectors {
+            let spec = deck_from_pysol_seed_str(seed).unwrap();
+            let got: Vec<u8> = spec.deck.iter().map(|c| c.index()).collect();
+            assert_eq!(got, expected, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn reader_and_text_extraction_agree_on_100_decks() {
+        let specs: Vec<DeckSpec> = generate_seed_range(1, 100)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        // Mix labelled dumps, bare lists split over lines, and junk brackets.
+        let mut text = String::new();
+        for (i, spec) in specs.iter().enumerate() {
+            match i % 3 {
+                0 => text.push_str(&spec.to_canonical_string()),
+                1 => {
+                    text.push_str("[not, a deck]\n");
+                    text.push_str(&spec.to_pysol_bracketed_string().replacen(", ", ",\n  ", 3));
+                    text.push('\n');
+                }
+                _ => text.push_str(&format!("Seed: {}\n{}\n", i, spec.to_pysol_bracketed_string())),
+            }
+        }
+
+        let from_text = extract_decks_from_text(&text, "dump");
+        assert_eq!(from_text.len(), 100);
+        assert_eq!(from_text, specs);
+        // A tiny buffer makes every line arrive in several pieces.
+        for capacity in [7, 8192] {
+            let reader = BufReader::with_capacity(capacity, text.as_bytes());
+            let from_reader = extract_decks_from_reader(reader, "dump");
+            assert_eq!(from_reader, from_text);
+            let labels = |v: &[DeckSpec]| v.iter().map(|s| s.label.clone()).collect::<Vec<_>>();
+            assert_eq!(labels(&from_reader), labels(&from_text));
+        }
+
+        let path = std::env::temp_dir().join(format!("klondike_decks_{}.txt", std::process::id()));
+        fs::write(&path, &text).unwrap();
+        let loaded = load_decks_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, from_text);
+        assert!(load_decks_from_file(&path).is_err());
+    }
+}
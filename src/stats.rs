@@ -1,11 +1,42 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::search::GameOutcome;
+
+/// Width (in characters) of the longest bar printed by `print_histogram`.
+const HISTOGRAM_BAR_WIDTH: u64 = 50;
+
+/// Winning line lengths are grouped into buckets of this many moves.
+const MOVE_COUNT_BUCKET: u16 = 10;
+
 #[derive(Default, Debug)]
 pub struct Stats {
     pub games_played: u64,
     pub games_won: u64,
     pub games_lost: u64,
+    /// Winning line length (bucketed: key = lower bound of a 10-move bucket)
+    /// -> number of won games.
+    pub move_count_histogram: BTreeMap<u16, u64>,
+    /// `GameOutcome::max_branch_depth` -> number of games (won or lost).
+    pub max_depth_histogram: BTreeMap<u16, u64>,
+    /// Sum of winning line lengths over won games with a recorded line.
+    pub total_winning_moves: u64,
+    /// Sum of `GameOutcome::nodes_visited` over all recorded games.
+    pub total_nodes_visited: u64,
 }
 
 impl Stats {
+    /// Stats for a whole batch: `record_outcome` applied to each outcome in
+    /// turn. Per-chunk results (e.g. from parallel workers) can then be
+    /// combined with `merge`.
+    pub fn batch_stats(outcomes: &[GameOutcome]) -> Stats {
+        let mut stats = Stats::default();
+        for outcome in outcomes {
+            stats.record_outcome(outcome);
+        }
+        stats
+    }
+
     pub fn record_win(&mut self) {
         self.games_played += 1;
         self.games_won += 1;
@@ -16,6 +47,28 @@ impl Stats {
         self.games_lost += 1;
     }
 
+    /// Record one finished search: counts the win/loss and updates both
+    /// histograms. The move-count histogram only covers games with a
+    /// recorded winning line.
+    pub fn record_outcome(&mut self, outcome: &GameOutcome) {
+        if outcome.is_win {
+            self.record_win();
+            if let Some(line) = outcome.winning_line.as_ref() {
+                self.total_winning_moves += line.len() as u64;
+                let len = u16::try_from(line.len()).unwrap_or(u16::MAX);
+                let bucket = len / MOVE_COUNT_BUCKET * MOVE_COUNT_BUCKET;
+                *self.move_count_histogram.entry(bucket).or_insert(0) += 1;
+            }
+        } else {
+            self.record_loss();
+        }
+        *self
+            .max_depth_histogram
+            .entry(outcome.max_branch_depth)
+            .or_insert(0) += 1;
+        self.total_nodes_visited += outcome.nodes_visited;
+    }
+
     pub fn win_rate(&self) -> f64 {
         if self.games_played == 0 {
             0.0
@@ -23,4 +76,312 @@ impl Stats {
             self.games_won as f64 / self.games_played as f64
         }
     }
+
+    /// `win_rate` as a percentage (0.0..=100.0).
+    pub fn win_percentage(&self) -> f64 {
+        self.win_rate() * 100.0
+    }
+
+    /// Mean winning line length over the games counted in
+    /// `move_count_histogram` (0.0 if there are none).
+    pub fn mean_winning_line_length(&self) -> f64 {
+        let wins_with_line: u64 = self.move_count_histogram.values().sum();
+        if wins_with_line == 0 {
+            0.0
+        } else {
+            self.total_winning_moves as f64 / wins_with_line as f64
+        }
+    }
+
+    /// Approximate `p`-quantile (`0.0..=1.0`, e.g. 0.5 for the median) of
+    /// the winning line lengths in `move_count_histogram`: the lower bound
+    /// of the bucket holding the `ceil(p * n)`-th shortest of the `n`
+    /// recorded lines. `None` if no win has a recorded line.
+    pub fn percentile_win_depth(&self, p: f64) -> Option<u16> {
+        let wins_with_line: u64 = self.move_count_histogram.values().sum();
+        if self.games_won == 0 || wins_with_line == 0 {
+            return None;
+        }
+        let target = ((p.clamp(0.0, 1.0) * wins_with_line as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (&bucket, &count) in &self.move_count_histogram {
+            seen += count;
+            if seen >= target {
+                return Some(bucket);
+            }
+        }
+        self.max_win_depth()
+    }
+
+    /// `mean_winning_line_length`, or `None` if no win has a recorded line.
+    /// Exact rather than bucketed: it uses `total_winning_moves`.
+    pub fn mean_win_depth(&self) -> Option<f64> {
+        let wins_with_line: u64 = self.move_count_histogram.values().sum();
+        (self.games_won > 0 && wins_with_line > 0).then(|| self.mean_winning_line_length())
+    }
+
+    /// Lower bound of the highest non-empty `move_count_histogram` bucket
+    /// (the longest winning line, to within `MOVE_COUNT_BUCKET` moves), or
+    /// `None` if no win has a recorded line.
+    pub fn max_win_depth(&self) -> Option<u16> {
+        if self.games_won == 0 {
+            return None;
+        }
+        self.move_count_histogram
+            .iter()
+            .rev()
+            .find(|&(_, &count)| count > 0)
+            .map(|(&bucket, _)| bucket)
+    }
+
+    /// Mean nodes visited per game recorded with `record_outcome`
+    /// (0.0 if there are none).
+    pub fn mean_nodes_visited(&self) -> f64 {
+        let recorded: u64 = self.max_depth_histogram.values().sum();
+        if recorded == 0 {
+            0.0
+        } else {
+            self.total_nodes_visited as f64 / recorded as f64
+        }
+    }
+
+    /// Add every counter and histogram bucket of `other` into `self`, e.g.
+    /// to combine per-thread stats from a parallel batch.
+    pub fn merge(&mut self, other: &Stats) {
+        self.games_played += other.games_played;
+        self.games_won += other.games_won;
+        self.games_lost += other.games_lost;
+        self.total_winning_moves += other.total_winning_moves;
+        self.total_nodes_visited += other.total_nodes_visited;
+        for (&k, &v) in &other.move_count_histogram {
+            *self.move_count_histogram.entry(k).or_insert(0) += v;
+        }
+        for (&k, &v) in &other.max_depth_histogram {
+            *self.max_depth_histogram.entry(k).or_insert(0) += v;
+        }
+    }
+
+    /// Multi-line summary: the `Display` line, then mean nodes visited and
+    /// mean winning line length (over wins with a recorded line). Every
+    /// line ends with a newline.
+    pub fn summary_string(&self) -> String {
+        format!(
+            "{}\nMean nodes visited: {:.0}\nMean winning line length: {:.1} moves\n",
+            self,
+            self.mean_nodes_visited(),
+            self.mean_winning_line_length()
+        )
+    }
+
+    /// Print both histograms as ASCII bar charts.
+    pub fn print_histogram(&self) {
+        println!("Winning line length (moves):");
+        print!("{}", render_histogram(&self.move_count_histogram, |k| {
+            format!("{:>4}-{:<4}", k, k.saturating_add(MOVE_COUNT_BUCKET - 1))
+        }));
+        println!("Max branch depth:");
+        print!("{}", render_histogram(&self.max_depth_histogram, |k| format!("{:>9}", k)));
+    }
+}
+
+/// One-line summary: `Played: 10  Won: 4  Lost: 6  Win rate: 40.0%`.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Played: {}  Won: {}  Lost: {}  Win rate: {:.1}%",
+            self.games_played,
+            self.games_won,
+            self.games_lost,
+            self.win_percentage()
+        )
+    }
+}
+
+/// Render one histogram, one line per key, with bars scaled so the largest
+/// count is `HISTOGRAM_BAR_WIDTH` characters wide.
+fn render_histogram(hist: &BTreeMap<u16, u64>, label: impl Fn(u16) -> String) -> String {
+    if hist.is_empty() {
+        return "  (no data)\n".to_string();
+    }
+    let max = hist.values().copied().max().unwrap_or(1).max(1);
+    let mut out = String::new();
+    for (&key, &count) in hist {
+        // Non-zero counts always get at least one '#'.
+        let width = (count * HISTOGRAM_BAR_WIDTH).div_ceil(max);
+        out.push_str(&format!(
+            "  {} | {} {}\n",
+            label(key),
+            "#".repeat(width as usize),
+            count
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::standard_deck;
+    use crate::game::TerminationReason;
+    use crate::moves::{Move, MoveKind};
+
+    fn outcome(is_win: bool, line_len: usize, depth: u16) -> GameOutcome {
+        GameOutcome {
+            initial_deck: standard_deck(),
+            is_win,
+            winning_line: is_win.then(|| vec![Move { kind: MoveKind::DealFromStock { cards_drawn: 3 } }; line_len]),
+            nodes_visited: 0,
+            termination: if is_win { TerminationReason::Win } else { TerminationReason::LossNoMoreMoves },
+            max_branch_depth: depth,
+            max_shelved: 0,
+            dead_end_branches: 0,
+            loop_pruned_branches: 0,
+            is_provably_optimal: false,
+            solve_duration_ms: 0,
+            nodes_per_second: 0.0,
+        }
+    }
+
+    #[test]
+    fn record_outcome_updates_counts_and_histograms() {
+        let mut stats = Stats::default();
+        stats.record_outcome(&outcome(true, 95, 120));
+        stats.record_outcome(&outcome(true, 99, 120));
+        stats.record_outcome(&outcome(true, 100, 130));
+        stats.record_outcome(&outcome(false, 0, 80));
+        stats.print_histogram();
+
+        assert_eq!(stats.games_played, 4);
+        assert_eq!(stats.games_won, 3);
+        assert_eq!(stats.games_lost, 1);
+        assert_eq!(
+            stats.move_count_histogram.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>(),
+            vec![(90, 2), (100, 1)]
+        );
+        assert_eq!(
+            stats.max_depth_histogram.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>(),
+            vec![(80, 1), (120, 2), (130, 1)]
+        );
+    }
+
+    #[test]
+    fn histogram_bars_scale_to_largest_count() {
+        let hist: BTreeMap<u16, u64> = [(0, 1), (10, 100)].into_iter().collect();
+        let out = render_histogram(&hist, |k| k.to_string());
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].matches('#').count(), 1);
+        assert_eq!(lines[1].matches('#').count(), HISTOGRAM_BAR_WIDTH as usize);
+        assert_eq!(render_histogram(&BTreeMap::new(), |k| k.to_string()), "  (no data)\n");
+    }
+
+    #[test]
+    fn merge_adds_counts_and_histograms() {
+        let mut a = Stats::default();
+        a.record_outcome(&outcome(true, 95, 120));
+        a.record_outcome(&outcome(false, 0, 80));
+        let mut b = Stats::default();
+        b.record_outcome(&outcome(true, 99, 120));
+        b.record_outcome(&outcome(true, 100, 130));
+
+        let mut all = Stats::default();
+        for o in [outcome(true, 95, 120), outcome(false, 0, 80), outcome(true, 99, 120), outcome(true, 100, 130)] {
+            all.record_outcome(&o);
+        }
+
+        a.merge(&b);
+        assert_eq!(a.games_played, all.games_played);
+        assert_eq!(a.games_won, all.games_won);
+        assert_eq!(a.games_lost, all.games_lost);
+        assert_eq!(a.move_count_histogram, all.move_count_histogram);
+        assert_eq!(a.max_depth_histogram, all.max_depth_histogram);
+        assert_eq!(a.total_winning_moves, all.total_winning_moves);
+        assert_eq!(a.total_nodes_visited, all.total_nodes_visited);
+
+        a.merge(&Stats::default());
+        assert_eq!(a.games_played, 4);
+    }
+
+    #[test]
+    fn win_percentage_and_one_line_summary() {
+        let mut stats = Stats::default();
+        assert_eq!(stats.win_percentage(), 0.0);
+        stats.record_win();
+        stats.record_loss();
+        stats.record_loss();
+        stats.record_loss();
+        assert_eq!(stats.win_percentage(), 25.0);
+        assert_eq!(stats.to_string(), "Played: 4  Won: 1  Lost: 3  Win rate: 25.0%");
+    }
+
+    #[test]
+    fn means_cover_wins_for_line_length_and_all_games_for_nodes() {
+        let mut stats = Stats::default();
+        assert_eq!(stats.mean_winning_line_length(), 0.0);
+        assert_eq!(stats.mean_nodes_visited(), 0.0);
+
+        stats.record_outcome(&GameOutcome { nodes_visited: 100, ..outcome(true, 90, 90) });
+        stats.record_outcome(&GameOutcome { nodes_visited: 300, ..outcome(true, 110, 110) });
+        stats.record_outcome(&GameOutcome { nodes_visited: 1100, ..outcome(false, 0, 50) });
+        assert_eq!(stats.mean_winning_line_length(), 100.0);
+        assert_eq!(stats.mean_nodes_visited(), 500.0);
+    }
+
+    #[test]
+    fn batch_stats_counts_a_slice_of_outcomes() {
+        let outcomes = [
+            GameOutcome { nodes_visited: 300, ..outcome(true, 80, 90) },
+            GameOutcome { nodes_visited: 600, ..outcome(true, 120, 130) },
+            GameOutcome { nodes_visited: 900, ..outcome(false, 0, 70) },
+        ];
+        let stats = Stats::batch_stats(&outcomes);
+        assert_eq!((stats.games_played, stats.games_won, stats.games_lost), (3, 2, 1));
+        assert!((stats.win_percentage() - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.mean_winning_line_length(), 100.0);
+        assert_eq!(stats.mean_nodes_visited(), 600.0);
+
+        let summary = stats.summary_string();
+        print!("{}", summary);
+        assert_eq!(
+            summary,
+            "Played: 3  Won: 2  Lost: 1  Win rate: 66.7%\n\
+             Mean nodes visited: 600\n\
+             Mean winning line length: 100.0 moves\n"
+        );
+
+        // Chunked batches merged back together match the one-shot batch.
+        let mut merged = Stats::batch_stats(&outcomes[..1]);
+        merged.merge(&Stats::batch_stats(&outcomes[1..]));
+        assert_eq!(merged.summary_string(), summary);
+        assert_eq!(Stats::batch_stats(&[]).games_played, 0);
+    }
+
+    #[test]
+    fn win_depth_percentiles_from_hand_crafted_histogram() {
+        // 10 wins: 2 in 80-89, 5 in 100-109, 2 in 120-129, 1 in 200-209.
+        let mut stats = Stats {
+            games_played: 12,
+            games_won: 10,
+            games_lost: 2,
+            total_winning_moves: 2 * 85 + 5 * 104 + 2 * 125 + 200,
+            ..Stats::default()
+        };
+        stats.move_count_histogram.extend([(80, 2), (100, 5), (120, 2), (200, 1)]);
+
+        assert_eq!(stats.percentile_win_depth(0.0), Some(80));
+        assert_eq!(stats.percentile_win_depth(0.2), Some(80));
+        assert_eq!(stats.percentile_win_depth(0.21), Some(100));
+        assert_eq!(stats.percentile_win_depth(0.5), Some(100));
+        assert_eq!(stats.percentile_win_depth(0.7), Some(100));
+        assert_eq!(stats.percentile_win_depth(0.9), Some(120));
+        assert_eq!(stats.percentile_win_depth(1.0), Some(200));
+        assert_eq!(stats.max_win_depth(), Some(200));
+        assert_eq!(stats.mean_win_depth(), Some(114.0));
+
+        let none = Stats { games_played: 3, games_lost: 3, ..Stats::default() };
+        assert_eq!(none.percentile_win_depth(0.5), None);
+        assert_eq!(none.mean_win_depth(), None);
+        assert_eq!(none.max_win_depth(), None);
+    }
 }
@@ -1,10 +1,14 @@
-//! Tableau representation for a Klondike (draw-3) game state.
+//! Tableau representation for a Klondike (draw-1 or draw-3) game state.
 //!
 //! This module defines fixed-capacity piles/columns and a compact `Tableau`
 //! type suitable for large-scale search. All cards are represented using the
 //! 1-byte `Card` type from `crate::card`.
 
 use crate::card::{Card, CARDS_PER_DECK, Suit, Rank};
+use crate::moves::{
+    can_make_any_move, can_move_to_foundation, is_valid_run, lowest_foundation_move, Move, MoveKind,
+};
+use crate::parse::ParseError;
 
 /// Number of tableau columns.
 pub const NUM_COLS: usize = 7;
@@ -30,10 +34,39 @@ pub const MAX_WASTE: usize = 24;
 /// up to 13 face-up cards (a full King..Ace run), so 19 is a safe bound.
 pub const MAX_COL: usize = 19;
 
+/// How many cards a single `DealFromStock` move turns over onto the waste.
+///
+/// Classic Klondike is played either "draw-1" or "draw-3". The rule is part
+/// of the tableau so that move application does not need any outside
+/// context; it never changes during a game.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawRule {
+    /// Turn over one card per deal.
+    Draw1,
+    /// Turn over up to three cards per deal (fewer if the stock runs out).
+    #[default]
+    Draw3,
+}
+
+impl DrawRule {
+    /// Maximum number of cards moved from stock to waste by one deal.
+    #[inline]
+    pub fn cards_per_deal(self) -> u8 {
+        match self {
+            DrawRule::Draw1 => 1,
+            DrawRule::Draw3 => 3,
+        }
+    }
+}
+
 /// A simple fixed-capacity stack-like pile.
 ///
 /// Index 0 is the "bottom" of the pile; `len - 1` is the top.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+///
+/// Equality and hashing look only at the active cards `cards[..len]`;
+/// slots above `len` may hold stale cards from earlier moves.
+#[derive(Clone, Copy, Debug)]
 pub struct Pile<const N: usize> {
     pub cards: [Card; N],
     pub len: u8, // number of active cards in `cards[..len]`
@@ -90,6 +123,71 @@ impl<const N: usize> Pile<N> {
     pub fn iter(&self) -> impl Iterator<Item = &Card> {
         self.cards[..(self.len as usize)].iter()
     }
+
+    /// Iterate over all cards from top to bottom (most recently pushed
+    /// first).
+    pub fn iter_rev(&self) -> impl Iterator<Item = &Card> {
+        self.cards[..(self.len as usize)].iter().rev()
+    }
+
+    /// The top `n` cards (clamped to the pile length), e.g. the cards
+    /// visible on a draw-3 waste.
+    ///
+    /// The slice borrows the pile's storage, so it is in storage order:
+    /// the *last* element is the top card. Use `.iter().rev()` on it for
+    /// top-to-bottom.
+    pub fn peek_n(&self, n: usize) -> &[Card] {
+        let len = self.len as usize;
+        &self.cards[len - n.min(len)..len]
+    }
+}
+
+impl<const N: usize> Default for Pile<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> PartialEq for Pile<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<const N: usize> Eq for Pile<N> {}
+
+impl<const N: usize> core::hash::Hash for Pile<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.cards[..self.len as usize].hash(state);
+    }
+}
+
+/// With the `serde` feature, a pile serializes as just its active cards,
+/// bottom to top.
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Pile<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Pile<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cards = Vec::<Card>::deserialize(deserializer)?;
+        if cards.len() > N {
+            return Err(serde::de::Error::custom(format!(
+                "pile has {} cards, capacity is {}",
+                cards.len(),
+                N
+            )));
+        }
+        let mut pile = Pile::new();
+        for card in cards {
+            pile.push(card);
+        }
+        Ok(pile)
+    }
 }
 
 /// A tableau column: some face-down cards at the top, then face-up cards.
@@ -97,7 +195,9 @@ impl<const N: usize> Pile<N> {
 /// As with `Pile`, index 0 is the "bottom" card and `len - 1` is the top.
 /// The first `num_face_down` cards (from index 0 upwards) are considered
 /// face-down; the rest (if any) are face-up.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+///
+/// As with `Pile`, equality and hashing ignore stale slots above `len`.
+#[derive(Clone, Copy, Debug)]
 pub struct Column<const N: usize> {
     pub cards: [Card; N],
     pub len: u8,
@@ -151,6 +251,32 @@ impl<const N: usize> Column<N> {
         }
     }
 
+    /// Shrink the column to its bottom `new_len` cards, dropping the rest;
+    /// `num_face_down` is capped at `new_len`, so cutting into the
+    /// face-down cards leaves a column with none face-up.
+    ///
+    /// Panics if `new_len > self.len()`.
+    pub fn truncate_to(&mut self, new_len: u8) {
+        assert!(new_len <= self.len, "truncate_to({}) on a column of {} cards", new_len, self.len);
+        self.len = new_len;
+        self.num_face_down = self.num_face_down.min(new_len);
+    }
+
+    /// Remove and return the top card if it is face-up (`None` for an
+    /// empty column or a face-down top). If that leaves only face-down
+    /// cards, the new top is turned face-up, as after a move in play.
+    pub fn pop_top_face_up(&mut self) -> Option<Card> {
+        if self.num_face_down >= self.len {
+            return None;
+        }
+        let card = self.cards[(self.len - 1) as usize];
+        self.len -= 1;
+        if self.len > 0 && self.num_face_down == self.len {
+            self.num_face_down -= 1;
+        }
+        Some(card)
+    }
+
     /// Peek at the top card (face-up or face-down; no visibility rules).
     pub fn top(&self) -> Option<Card> {
         if self.len == 0 {
@@ -170,10 +296,88 @@ impl<const N: usize> Column<N> {
         let start = self.num_face_down as usize;
         self.cards[start..(self.len as usize)].iter()
     }
+
+    /// The playable run at the exposed end of the column: the longest
+    /// suffix of the face-up cards that `is_valid_run` accepts, as a view
+    /// into the column's storage.
+    ///
+    /// Empty for an empty column or one with no face-up cards; otherwise
+    /// holds at least the exposed card.
+    pub fn valid_run_from_bottom(&self) -> &[Card] {
+        let face_up = &self.cards[self.num_face_down as usize..self.len as usize];
+        let n = face_up.len();
+        let mut run = 0;
+        while run < n && is_valid_run(&face_up[n - run - 1..]) {
+            run += 1;
+        }
+        &face_up[n - run..]
+    }
+}
+
+impl<const N: usize> Default for Column<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> PartialEq for Column<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_face_down == other.num_face_down && self.iter_all().eq(other.iter_all())
+    }
+}
+
+impl<const N: usize> Eq for Column<N> {}
+
+impl<const N: usize> core::hash::Hash for Column<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.cards[..self.len as usize].hash(state);
+        self.num_face_down.hash(state);
+    }
+}
+
+/// Serialized form of a `Column`: the active cards (bottom to top) and how
+/// many of them are face-down.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ColumnRepr {
+    cards: Vec<Card>,
+    num_face_down: u8,
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Column<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ColumnRepr {
+            cards: self.iter_all().copied().collect(),
+            num_face_down: self.num_face_down,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Column<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ColumnRepr::deserialize(deserializer)?;
+        if repr.cards.len() > N || repr.num_face_down as usize > repr.cards.len() {
+            return Err(serde::de::Error::custom(format!(
+                "invalid column: {} cards ({} face-down), capacity is {}",
+                repr.cards.len(),
+                repr.num_face_down,
+                N
+            )));
+        }
+        let mut col = Column::new();
+        for (i, card) in repr.cards.into_iter().enumerate() {
+            col.push(card, i < repr.num_face_down as usize);
+        }
+        Ok(col)
+    }
 }
 
 /// Full tableau state for a Klondike game.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tableau {
     /// Stock pile (face-down draw pile).
     pub stock: Pile<MAX_STOCK>,
@@ -184,16 +388,20 @@ pub struct Tableau {
     /// Foundations (one per suit), stored as rank numbers:
     /// 0 = empty, 1 = Ace, ..., 13 = King.
     pub foundations: [u8; NUM_FOUNDATIONS],
+    /// Draw rule in effect for `DealFromStock` (draw-1 or draw-3).
+    pub draw_rule: DrawRule,
 }
 
 impl Tableau {
-    /// Create an entirely empty tableau (no cards anywhere).
+    /// Create an entirely empty tableau (no cards anywhere), using the
+    /// default draw-3 rule.
     pub fn new_empty() -> Self {
         Self {
             stock: Pile::new(),
             waste: Pile::new(),
             columns: [Column::new(); NUM_COLS],
             foundations: [0; NUM_FOUNDATIONS],
+            draw_rule: DrawRule::default(),
         }
     }
 
@@ -202,6 +410,195 @@ impl Tableau {
         self.foundations.iter().all(|&r| r == 13)
     }
 
+    /// Number of cards not yet on the foundations. Each needs at least one
+    /// more move, so this is an admissible lower bound on the moves left to
+    /// win.
+    pub fn remaining_to_win(&self) -> u8 {
+        CARDS_PER_DECK - self.foundations.iter().sum::<u8>()
+    }
+
+    /// Fraction of the deck on the foundations: 0.0 at the deal, 1.0 for a
+    /// win.
+    pub fn foundation_progress_score(&self) -> f64 {
+        self.foundations.iter().sum::<u8>() as f64 / CARDS_PER_DECK as f64
+    }
+
+    /// True if the game is lost where it stands: no legal move at all
+    /// (not even a deal or redeal) and not already won.
+    ///
+    /// Same answer as `generate_legal_moves(self).is_empty() && !self.is_win()`,
+    /// but stops at the first legal move found and does not allocate.
+    pub fn is_stuck(&self) -> bool {
+        !can_make_any_move(self) && !self.is_win()
+    }
+
+    /// Cards that can currently be picked up on their own: the face-up top
+    /// card of each column that has one, in column order, then the waste
+    /// top. At most 8 cards.
+    pub fn accessible_top_cards(&self) -> Vec<Card> {
+        let mut cards: Vec<Card> = self.iter_face_up_tops().map(|(_, card)| card).collect();
+        cards.extend(self.waste.top());
+        cards
+    }
+
+    /// True if any of `accessible_top_cards` can go to its foundation
+    /// right now.
+    pub fn can_reach_foundation_immediately(&self) -> bool {
+        self.accessible_top_cards()
+            .into_iter()
+            .any(|card| can_move_to_foundation(self, card))
+    }
+
+    /// Number of distinct cards `reachable_waste_cards` finds: how many
+    /// stock/waste cards can be brought to the waste top by dealing and
+    /// redealing alone. From a fresh deal that is `ceil(stock / 3)` under
+    /// draw-3 and every stock card under draw-1.
+    pub fn stock_accessible_count(&self) -> u8 {
+        reachable_waste_cards(self).len() as u8
+    }
+
+    /// Number of face-down cards across all columns.
+    ///
+    /// Stock cards are not counted: they are unseen, but their order is
+    /// fixed and dealing reveals them without any column play.
+    pub fn num_hidden_cards(&self) -> u8 {
+        self.columns.iter().map(|col| col.num_face_down).sum()
+    }
+
+    /// Number of face-up cards across all columns.
+    pub fn num_face_up_tableau_cards(&self) -> u8 {
+        self.columns.iter().map(|col| col.num_face_up()).sum()
+    }
+
+    /// Number of cards in each column, in column order.
+    pub fn pile_heights(&self) -> [u8; NUM_COLS] {
+        self.columns.map(|col| col.len)
+    }
+
+    /// Number of face-down cards in each column, in column order.
+    pub fn column_face_down_counts(&self) -> [u8; NUM_COLS] {
+        self.columns.map(|col| col.num_face_down)
+    }
+
+    /// Height of the shortest column (0 if any column is empty).
+    pub fn min_pile_height(&self) -> u8 {
+        self.pile_heights().into_iter().min().unwrap_or(0)
+    }
+
+    /// Height of the tallest column.
+    pub fn max_pile_height(&self) -> u8 {
+        self.pile_heights().into_iter().max().unwrap_or(0)
+    }
+
+    /// True if no column has any face-down cards left.
+    ///
+    /// From here the game needs no more decisions: `auto_complete_moves`
+    /// can usually finish it by playing the lowest available card to its
+    /// foundation, dealing through the stock when nothing else is playable.
+    pub fn is_auto_completable(&self) -> bool {
+        self.num_hidden_cards() == 0
+    }
+
+    /// Greedy move sequence that finishes the game from an auto-completable
+    /// tableau.
+    ///
+    /// At each step the lowest-ranked card that can go to a foundation is
+    /// played (`ColumnToFoundation` / `WasteToFoundation`); when none can,
+    /// the stock is dealt (or redealt) to bring a new waste card up.
+    ///
+    /// Returns an empty `Vec` if `is_auto_completable()` is false, or if a
+    /// whole pass through the stock makes no progress (possible under
+    /// draw-3, where some stock cards never reach the top of the waste
+    /// without playing other cards onto the columns).
+    pub fn auto_complete_moves(&self) -> Vec<Move> {
+        if !self.is_auto_completable() {
+            return Vec::new();
+        }
+
+        let mut tab = *self;
+        let mut line = Vec::new();
+        // Deals/redeals since the last foundation play. A full stock cycle
+        // is at most one deal per stock/waste card plus one redeal.
+        let mut idle_steps = 0u16;
+        while !tab.is_win() {
+            let mv = match lowest_foundation_move(&tab) {
+                Some(mv) => {
+                    idle_steps = 0;
+                    mv
+                }
+                None => {
+                    idle_steps += 1;
+                    if idle_steps > tab.stock.len() as u16 + tab.waste.len() as u16 + 1 {
+                        return Vec::new();
+                    }
+                    if !tab.stock.is_empty() {
+                        Move::deal_from_stock(&tab)
+                    } else if !tab.waste.is_empty() {
+                        Move { kind: MoveKind::RedealStock }
+                    } else {
+                        return Vec::new();
+                    }
+                }
+            };
+            mv.apply(&mut tab);
+            line.push(mv);
+        }
+        line
+    }
+
+    /// Length of the descending, alternating-color run ending at the exposed
+    /// card of column `col_idx`, counted within the face-up cards only.
+    ///
+    /// This is the largest block `ColumnToColumn` could move from that
+    /// column (see `is_valid_run`). An empty column gives 0; any non-empty
+    /// column gives at least 1.
+    ///
+    /// Panics if `col_idx >= NUM_COLS`.
+    pub fn face_up_run_length(&self, col_idx: usize) -> u8 {
+        self.columns[col_idx].valid_run_from_bottom().len() as u8
+    }
+
+    /// The top card of column `col_idx` if it is face-up, or `None` when
+    /// the column is empty or its top card is still face-down.
+    ///
+    /// Panics if `col_idx >= NUM_COLS`.
+    #[inline]
+    pub fn column_top_face_up_card(&self, col_idx: usize) -> Option<Card> {
+        let col = &self.columns[col_idx];
+        if col.len > col.num_face_down {
+            Some(col.cards[(col.len - 1) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// `(col_idx, top card)` for every column whose top card is face-up,
+    /// in column order; empty and all-face-down columns are skipped.
+    pub fn iter_face_up_tops(&self) -> impl Iterator<Item = (usize, Card)> + '_ {
+        (0..NUM_COLS).filter_map(|col_idx| Some((col_idx, self.column_top_face_up_card(col_idx)?)))
+    }
+
+    /// Whether the tableau satisfies the invariants move generation relies
+    /// on: every column has `num_face_down <= len` and, if non-empty, a
+    /// face-up top card; every foundation rank is at most 13; and the
+    /// tableau holds all 52 cards (`total_cards`).
+    ///
+    /// Unlike `GameState::assert_consistent` this needs no move history and
+    /// returns instead of panicking.
+    pub fn is_column_invariant_valid(&self) -> bool {
+        self.piles_are_well_formed() && self.total_cards() == CARDS_PER_DECK
+    }
+
+    /// The column and foundation checks of `is_column_invariant_valid`,
+    /// without the 52-card total.
+    pub(crate) fn piles_are_well_formed(&self) -> bool {
+        let columns_ok = self
+            .columns
+            .iter()
+            .all(|col| col.num_face_down <= col.len && (col.len == 0 || col.num_face_down < col.len));
+        columns_ok && self.foundations.iter().all(|&r| r <= 13)
+    }
+
     /// Total number of cards in stock + waste + columns + foundations.
     ///
     /// Foundations are counted using their rank number, which is also the
@@ -224,6 +621,37 @@ impl Tableau {
         sum as u8
     }
 
+    /// Copy of this tableau with the 7 columns sorted into a canonical
+    /// order (see `sort_columns_canonically`), so tableaus that differ only
+    /// in which column holds which pile come out identical (and hash
+    /// identically). Each column keeps its cards and face-down count;
+    /// stock, waste and foundations are untouched.
+    ///
+    /// Moves name columns by index, so a search that keys its visited set
+    /// on the canonical form must still generate moves from the original.
+    pub fn flatten_to_canonical(&self) -> Tableau {
+        let mut out = *self;
+        out.sort_columns_canonically();
+        out
+    }
+
+    /// Swap columns `a` and `b` in place.
+    ///
+    /// Panics if `a` or `b` is `>= NUM_COLS`.
+    pub fn swap_columns(&mut self, a: usize, b: usize) {
+        self.columns.swap(a, b);
+    }
+
+    /// Sort the 7 columns in place by `(num_face_down, len, bottom card
+    /// index)`, empty columns first. The sort is stable, and non-empty
+    /// columns never share a bottom card, so any two tableaus that are
+    /// column permutations of each other compare equal afterwards. This
+    /// is the order `flatten_to_canonical` uses.
+    pub fn sort_columns_canonically(&mut self) {
+        self.columns
+            .sort_by_key(|col| (col.num_face_down, col.len, col.iter_all().next().map(|c| c.0)));
+    }
+
     /// Flatten this tableau into a canonical 52-card sequence of `Card`s.
     ///
     /// The order is:
@@ -287,6 +715,204 @@ impl Tableau {
         out
     }
 
+    /// Encode the full tableau as a single line of text.
+    ///
+    /// Sections are separated by `;`:
+    ///
+    /// ```text
+    /// F:AH|2C|0S|0D;S:7C,9H;W:3D;C1:ks,qd,6S;C2:...;...;C7:...;D:3
+    /// ```
+    ///
+    ///   - `F`: top card of each foundation in `Suit::ALL` order, or `0` plus
+    ///     the suit character when empty.
+    ///   - `S`, `W`: stock and waste, bottom to top.
+    ///   - `C1`..`C7`: each column bottom to top; face-down cards are written
+    ///     in lowercase, face-up cards in uppercase.
+    ///   - `D`: draw rule, `1` or `3`.
+    ///
+    /// Face-down cards are written out (rather than masked) so that
+    /// `from_compact_str` can rebuild the exact tableau.
+    pub fn to_compact_str(&self) -> String {
+        let cards = |it: &mut dyn Iterator<Item = &Card>| -> String {
+            it.map(|c| c.ascii_str()).collect::<Vec<_>>().join(",")
+        };
+
+        let foundations: Vec<String> = self
+            .foundations
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| {
+                let suit = Suit::ALL[i];
+                if n == 0 {
+                    format!("0{}", suit.short_char())
+                } else {
+                    Card::new(suit, Rank::from_u8(n - 1)).ascii_str()
+                }
+            })
+            .collect();
+
+        let mut out = format!(
+            "F:{};S:{};W:{}",
+            foundations.join("|"),
+            cards(&mut self.stock.iter()),
+            cards(&mut self.waste.iter())
+        );
+        for (c, col) in self.columns.iter().enumerate() {
+            let entries: Vec<String> = col
+                .iter_all()
+                .enumerate()
+                .map(|(i, card)| {
+                    if i < col.num_face_down as usize {
+                        card.ascii_str().to_ascii_lowercase()
+                    } else {
+                        card.ascii_str()
+                    }
+                })
+                .collect();
+            out.push_str(&format!(";C{}:{}", c + 1, entries.join(",")));
+        }
+        out.push_str(&format!(";D:{}", self.draw_rule.cards_per_deal()));
+        out
+    }
+
+    /// Parse the format produced by `to_compact_str`.
+    ///
+    /// Sections may appear in any order; `D` is optional (draw-3 if absent).
+    /// The result must hold each of the 52 cards exactly once.
+    pub fn from_compact_str(s: &str) -> Result<Tableau, ParseError> {
+        fn card_list(value: &str) -> Result<Vec<(Card, bool)>, ParseError> {
+            if value.is_empty() {
+                return Ok(Vec::new());
+            }
+            value
+                .split(',')
+                .map(|tok| {
+                    let tok = tok.trim();
+                    let card = Card::from_short_str(tok)
+                        .ok_or_else(|| ParseError::BadCard(tok.to_string()))?;
+                    let face_down = !tok.chars().any(|ch| ch.is_ascii_uppercase());
+                    Ok((card, face_down))
+                })
+                .collect()
+        }
+
+        fn check_capacity(key: &str, count: usize, capacity: usize) -> Result<(), ParseError> {
+            if count > capacity {
+                return Err(ParseError::TooManyCards {
+                    section: key.to_string(),
+                    count,
+                    capacity,
+                });
+            }
+            Ok(())
+        }
+
+        let mut tab = Tableau::new_empty();
+        let mut seen_keys: Vec<&str> = Vec::new();
+        let mut seen_cards = [false; CARDS_PER_DECK as usize];
+        let mut mark = |card: Card| -> Result<(), ParseError> {
+            if std::mem::replace(&mut seen_cards[card.index() as usize], true) {
+                return Err(ParseError::DuplicateCard(card.ascii_str()));
+            }
+            Ok(())
+        };
+
+        for section in s.trim().split(';') {
+            let (key, value) = section
+                .split_once(':')
+                .ok_or_else(|| ParseError::MalformedSection(section.to_string()))?;
+            let key = key.trim();
+            let value = value.trim();
+            if seen_keys.contains(&key) {
+                return Err(ParseError::UnexpectedSection(key.to_string()));
+            }
+            seen_keys.push(key);
+
+            match key {
+                "F" => {
+                    let entries: Vec<&str> = value.split('|').collect();
+                    if entries.len() != NUM_FOUNDATIONS {
+                        return Err(ParseError::BadFoundation(value.to_string()));
+                    }
+                    for (i, entry) in entries.into_iter().enumerate() {
+                        let suit = Suit::ALL[i];
+                        let bad = || ParseError::BadFoundation(entry.to_string());
+                        let top = if entry.len() == 2 && entry.starts_with('0') {
+                            if !entry[1..].eq_ignore_ascii_case(&suit.short_char().to_string()) {
+                                return Err(bad());
+                            }
+                            0
+                        } else {
+                            let card = Card::from_short_str(entry).ok_or_else(bad)?;
+                            if card.suit() != suit {
+                                return Err(bad());
+                            }
+                            card.rank_number()
+                        };
+                        for r in 0..top {
+                            mark(Card::new(suit, Rank::from_u8(r)))?;
+                        }
+                        tab.foundations[i] = top;
+                    }
+                }
+                "S" | "W" => {
+                    let cards = card_list(value)?;
+                    if key == "S" {
+                        check_capacity(key, cards.len(), MAX_STOCK)?;
+                    } else {
+                        check_capacity(key, cards.len(), MAX_WASTE)?;
+                    }
+                    for (card, _) in cards {
+                        mark(card)?;
+                        if key == "S" {
+                            tab.stock.push(card);
+                        } else {
+                            tab.waste.push(card);
+                        }
+                    }
+                }
+                "D" => {
+                    tab.draw_rule = match value {
+                        "1" => DrawRule::Draw1,
+                        "3" => DrawRule::Draw3,
+                        _ => return Err(ParseError::BadDrawRule(value.to_string())),
+                    };
+                }
+                _ => {
+                    // Only the canonical `C1`..`C7` spellings are accepted,
+                    // so `C01` or `C+1` cannot name a column a second time.
+                    let c = key
+                        .strip_prefix('C')
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|n| (1..=NUM_COLS).contains(n) && key == format!("C{}", n))
+                        .ok_or_else(|| ParseError::UnexpectedSection(key.to_string()))?
+                        - 1;
+                    let cards = card_list(value)?;
+                    let col = &mut tab.columns[c];
+                    check_capacity(key, col.len as usize + cards.len(), MAX_COL)?;
+                    for (card, face_down) in cards {
+                        if face_down && col.num_face_up() > 0 {
+                            return Err(ParseError::FaceDownAboveFaceUp { column: c });
+                        }
+                        mark(card)?;
+                        col.push(card, face_down);
+                    }
+                }
+            }
+        }
+
+        let required = ["F", "S", "W", "C1", "C2", "C3", "C4", "C5", "C6", "C7"];
+        if let Some(missing) = required.iter().find(|k| !seen_keys.contains(k)) {
+            return Err(ParseError::MissingSection(missing.to_string()));
+        }
+        let total = tab.total_cards() as usize;
+        if total != CARDS_PER_DECK as usize {
+            return Err(ParseError::WrongCardCount(total));
+        }
+
+        Ok(tab)
+    }
+
     /// Deal a standard Klondike initial tableau from a *dealing-order* deck.
     ///
     /// This matches PySolFC's `Klondike.startGame(flip=0, reverse=1)` logic:
@@ -298,8 +924,23 @@ impl Tableau {
     ///   one face-up card on top of each column.
     /// - The remaining 24 cards form the stock such that the next draw from
     ///   stock corresponds to the next card in `deck`.
+    ///
+    /// The resulting tableau uses the default draw-3 rule; see
+    /// `deal_from_shuffled_with_rule` to choose draw-1 instead.
     pub fn deal_from_shuffled(deck: [Card; CARDS_PER_DECK as usize]) -> Self {
+        Self::deal_from_shuffled_with_rule(deck, DrawRule::default())
+    }
+
+    /// Same as `deal_from_shuffled`, but with an explicit draw rule.
+    ///
+    /// The layout of the deal itself does not depend on the draw rule; only
+    /// later `DealFromStock` moves do.
+    pub fn deal_from_shuffled_with_rule(
+        deck: [Card; CARDS_PER_DECK as usize],
+        draw_rule: DrawRule,
+    ) -> Self {
         let mut t = Tableau::new_empty();
+        t.draw_rule = draw_rule;
         let mut idx: usize = 0; // next card to consume from `deck`
 
         // 1) 6 rounds of face-down dealing.
@@ -334,6 +975,84 @@ impl Tableau {
 
         t
     }
+
+    /// Inverse of `deal_from_shuffled`: recover the dealing-order deck as raw
+    /// card indices, i.e. the array `game::layout_from_imported_deck_indices`
+    /// was given.
+    ///
+    /// The indices are in the same space as the lists printed by
+    /// `dump_pysolfc_deal.py` (already mapped to `Card::index()`), so the
+    /// result can be fed straight back to the Python tooling via
+    /// `DeckSpec::to_pysol_bracketed_string`.
+    ///
+    /// Only meaningful for a freshly dealt tableau (no moves played).
+    pub fn export_pysol_indices(&self) -> [u8; CARDS_PER_DECK as usize] {
+        debug_assert!(
+            self.waste.is_empty() && self.foundations.iter().all(|&f| f == 0),
+            "export_pysol_indices requires a freshly dealt tableau"
+        );
+        debug_assert!(
+            self.columns
+                .iter()
+                .enumerate()
+                .all(|(c, col)| col.len() as usize == c + 1 && col.num_face_down() as usize == c),
+            "export_pysol_indices requires a freshly dealt tableau"
+        );
+
+        let mut out = [0u8; CARDS_PER_DECK as usize];
+        let mut idx: usize = 0;
+
+        // Same visiting order as the deal: round r put its card at
+        // `cards[r - 1]` of each column it dealt to.
+        for round_start in 1..NUM_COLS {
+            for col in (round_start..NUM_COLS).rev() {
+                out[idx] = self.columns[col].cards[round_start - 1].index();
+                idx += 1;
+            }
+        }
+        for col in (0..NUM_COLS).rev() {
+            out[idx] = self.columns[col].cards[col].index();
+            idx += 1;
+        }
+
+        // The stock is stored reversed: its top is the next card dealt.
+        let remaining = self.stock.len as usize;
+        for i in 0..remaining {
+            out[idx + i] = self.stock.cards[remaining - 1 - i].index();
+        }
+
+        out
+    }
+}
+
+/// The cards that can be the waste top using only `DealFromStock` and
+/// `RedealStock` from `tab`, in the order they first show up (starting
+/// with the current waste top, if any).
+///
+/// Under draw-3 only every third card of a pass surfaces (plus the last
+/// card of a short final group). The rest of the current pass is dealt,
+/// then one full pass after a redeal; later passes repeat that one.
+pub fn reachable_waste_cards(tab: &Tableau) -> Vec<Card> {
+    let redeal = Move { kind: MoveKind::RedealStock };
+    let mut t = *tab;
+    let mut cards: Vec<Card> = Vec::new();
+    let mut redealt = false;
+    loop {
+        if let Some(top) = t.waste.top()
+            && !cards.contains(&top)
+        {
+            cards.push(top);
+        }
+        if !t.stock.is_empty() {
+            Move::deal_from_stock(&t).apply(&mut t);
+        } else if !t.waste.is_empty() && !redealt {
+            redeal.apply(&mut t);
+            redealt = true;
+        } else {
+            break;
+        }
+    }
+    cards
 }
 
 #[cfg(test)]
@@ -373,6 +1092,122 @@ mod tests {
         assert_eq!(t.total_cards(), 52);
     }
 
+    #[test]
+    fn remaining_to_win_and_progress_track_foundations() {
+        let mut t = Tableau::new_empty();
+        assert_eq!(t.remaining_to_win(), 52);
+        assert_eq!(t.foundation_progress_score(), 0.0);
+
+        t.foundations = [13, 13, 0, 0];
+        assert_eq!(t.remaining_to_win(), 26);
+        assert_eq!(t.foundation_progress_score(), 0.5);
+
+        t.foundations = [13; NUM_FOUNDATIONS];
+        assert_eq!(t.remaining_to_win(), 0);
+        assert_eq!(t.foundation_progress_score(), 1.0);
+    }
+
+    #[test]
+    fn auto_complete_finishes_when_nothing_is_face_down() {
+        let c = Card::new;
+        let mut t = Tableau::new_empty();
+        t.draw_rule = DrawRule::Draw1;
+        t.foundations = [11; NUM_FOUNDATIONS]; // Ace..Jack of every suit
+        t.columns[0].push(c(Suit::Hearts, Rank::King), false);
+        t.columns[0].push(c(Suit::Clubs, Rank::Queen), false);
+        t.columns[1].push(c(Suit::Clubs, Rank::King), false);
+        t.columns[1].push(c(Suit::Hearts, Rank::Queen), false);
+        t.waste.push(c(Suit::Spades, Rank::King));
+        t.stock.push(c(Suit::Diamonds, Rank::King));
+        t.stock.push(c(Suit::Spades, Rank::Queen));
+        t.stock.push(c(Suit::Diamonds, Rank::Queen));
+        assert_eq!(t.total_cards(), 52);
+        assert!(t.is_auto_completable());
+
+        let line = t.auto_complete_moves();
+        let mut replay = t;
+        for mv in &line {
+            mv.apply(&mut replay);
+        }
+        assert!(replay.is_win());
+        assert!(line.iter().any(|mv| matches!(mv.kind, MoveKind::DealFromStock { .. })));
+        assert!(line.iter().all(|mv| !matches!(mv.kind, MoveKind::ColumnToColumn { .. })));
+    }
+
+    /// `is_stuck` must agree with the allocating definition on every
+    /// position of many random games.
+    #[test]
+    fn is_stuck_matches_empty_move_list() {
+        use crate::moves::generate_legal_moves;
+
+        let mut stuck_seen = 0;
+        for seed in 0..200 {
+            let mut tab = Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(seed));
+            let mut rng: u32 = seed;
+            for _ in 0..300 {
+                let moves = generate_legal_moves(&tab);
+                assert_eq!(tab.is_stuck(), moves.is_empty() && !tab.is_win());
+                if moves.is_empty() {
+                    stuck_seen += 1;
+                    break;
+                }
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                // Prefer anything but stock cycling so games can run dry.
+                let useful: Vec<&Move> = moves
+                    .iter()
+                    .filter(|m| !matches!(m.kind, MoveKind::DealFromStock { .. } | MoveKind::RedealStock))
+                    .collect();
+                let mv = if useful.is_empty() || rng.is_multiple_of(4) {
+                    moves[(rng >> 8) as usize % moves.len()]
+                } else {
+                    *useful[(rng >> 8) as usize % useful.len()]
+                };
+                mv.apply(&mut tab);
+            }
+        }
+        println!("stuck positions reached: {}", stuck_seen);
+    }
+
+    #[test]
+    fn stuck_when_nothing_can_move() {
+        let c = Card::new;
+        // Seven red column tops (nothing fits on anything, no empty column
+        // for a King), an Ace hidden in column 0, empty stock and waste.
+        let mut t = Tableau::new_empty();
+        t.columns[0].push(c(Suit::Spades, Rank::Ace), true);
+        let tops = [
+            c(Suit::Hearts, Rank::King),
+            c(Suit::Diamonds, Rank::King),
+            c(Suit::Hearts, Rank::Queen),
+            c(Suit::Diamonds, Rank::Queen),
+            c(Suit::Hearts, Rank::Jack),
+            c(Suit::Diamonds, Rank::Jack),
+            c(Suit::Hearts, Rank::Ten),
+        ];
+        for (col, top) in t.columns.iter_mut().zip(tops) {
+            col.push(top, false);
+        }
+        assert!(t.is_stuck());
+
+        // One card in the waste makes RedealStock legal.
+        t.waste.push(c(Suit::Clubs, Rank::Five));
+        assert!(!t.is_stuck());
+
+        // Any card left in the stock makes DealFromStock legal, so a fresh
+        // deal is never stuck, not even `canonical_unplayable_deck` (whose
+        // "no-moves" property is about the opening columns only).
+        let fresh =
+            Tableau::deal_from_shuffled(crate::canonical_decks::canonical_unplayable_deck());
+        assert!(!fresh.is_stuck());
+    }
+
+    #[test]
+    fn auto_complete_is_empty_while_cards_are_face_down() {
+        let t = Tableau::deal_from_shuffled(standard_deck());
+        assert!(!t.is_auto_completable());
+        assert!(t.auto_complete_moves().is_empty());
+    }
+
     #[test]
     fn deal_from_standard_deck_initial_klondike_layout() {
         let deck = standard_deck();
@@ -427,10 +1262,11 @@ mod tests {
             "some card indices were not dealt"
         );
 
-        // Spot-check: top of stock is the last card in the deck.
+        // Spot-check: top of stock is the next undealt card (deck[28]),
+        // i.e. the first card a draw from stock will expose.
         let top_stock = t.stock.top().unwrap();
-        let last_deck = deck[(CARDS_PER_DECK as usize) - 1];
-        assert_eq!(top_stock, last_deck);
+        let next_deck = deck[CARDS_PER_DECK as usize - MAX_STOCK];
+        assert_eq!(top_stock, next_deck);
     }
 
     #[test]
@@ -450,10 +1286,448 @@ mod tests {
 
         let face_up: Vec<String> = col
             .iter_face_up()
-            .map(|c| c.short_str())
+            .map(|c| c.ascii_str())
             .collect();
 
         // Two face-up cards: "4D" and "5H"
         assert_eq!(face_up, vec!["4D".to_string(), "5H".to_string()]);
     }
+
+    #[test]
+    fn compact_str_round_trip_fresh_deal() {
+        let deck = crate::card::shuffled_deck_from_seed(21);
+        let tab = Tableau::deal_from_shuffled_with_rule(deck, DrawRule::Draw1);
+
+        let s = tab.to_compact_str();
+        println!("{}", s);
+        assert!(s.starts_with("F:0H|0C|0S|0D;S:"));
+        assert!(s.ends_with(";D:1"));
+
+        assert_eq!(Tableau::from_compact_str(&s), Ok(tab));
+    }
+
+    #[test]
+    fn compact_str_round_trip_after_50_moves() {
+        use crate::moves::generate_legal_moves;
+
+        let deck = crate::card::shuffled_deck_from_seed(22);
+        let mut tab = Tableau::deal_from_shuffled(deck);
+        let mut rng: u32 = 7;
+        for _ in 0..50 {
+            let moves = generate_legal_moves(&tab);
+            if moves.is_empty() {
+                break;
+            }
+            rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+            moves[(rng >> 8) as usize % moves.len()].apply(&mut tab);
+        }
+
+        let s = tab.to_compact_str();
+        println!("{}", s);
+        let parsed = Tableau::from_compact_str(&s).expect("round-trip parse");
+        assert_eq!(parsed, tab);
+        assert_eq!(parsed.to_compact_str(), s);
+    }
+
+    #[test]
+    fn compact_str_rejects_bad_input() {
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+        let good = tab.to_compact_str();
+        let stock_top = tab.stock.top().unwrap().ascii_str();
+        let c1_card = tab.columns[0].cards[0].ascii_str();
+
+        // Replace one section of `good` with `new_section`.
+        let with_section = |key: &str, new_section: &str| -> String {
+            good.split(';')
+                .map(|sec| {
+                    if sec.split(':').next() == Some(key) {
+                        new_section.to_string()
+                    } else {
+                        sec.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(";")
+        };
+
+        assert_eq!(
+            Tableau::from_compact_str(&with_section("W", "W:ZZ")),
+            Err(ParseError::BadCard("ZZ".to_string()))
+        );
+        assert_eq!(
+            Tableau::from_compact_str(&with_section("S", "S:")),
+            Err(ParseError::WrongCardCount(28))
+        );
+        assert_eq!(
+            Tableau::from_compact_str(&with_section("W", &format!("W:{}", c1_card))),
+            Err(ParseError::DuplicateCard(c1_card.clone()))
+        );
+        assert_eq!(
+            Tableau::from_compact_str(&with_section("C7", "W:")),
+            Err(ParseError::UnexpectedSection("W".to_string()))
+        );
+        assert_eq!(
+            Tableau::from_compact_str(&with_section("D", "D:2")),
+            Err(ParseError::BadDrawRule("2".to_string()))
+        );
+
+        // `C01` and `C+1` would also parse to column 1; they are rejected
+        // rather than piling a second section onto the same column.
+        for alias in ["C01", "C+1"] {
+            let renamed = good.replacen("C1:", &format!("{}:", alias), 1);
+            assert_eq!(
+                Tableau::from_compact_str(&renamed),
+                Err(ParseError::UnexpectedSection(alias.to_string()))
+            );
+        }
+        let deck = standard_deck();
+        let run = |cards: &[Card]| cards.iter().map(|c| c.ascii_str()).collect::<Vec<_>>().join(",");
+        let overflow = format!("C1:{};C01:{}", run(&deck[..19]), run(&deck[19..38]));
+        assert_eq!(
+            Tableau::from_compact_str(&overflow),
+            Err(ParseError::UnexpectedSection("C01".to_string()))
+        );
+
+        // Column 1 holds one face-up card; put a face-down card above it
+        // (taken from the stock, and removed there to keep 52 cards).
+        let stock_rest = good
+            .split(';')
+            .find(|sec| sec.starts_with("S:"))
+            .unwrap()
+            .replacen(&format!(",{}", stock_top), "", 1);
+        let moved = with_section("S", &stock_rest);
+        let bad_col = moved.replacen(
+            &format!("C1:{}", c1_card),
+            &format!("C1:{},{}", c1_card, stock_top.to_ascii_lowercase()),
+            1,
+        );
+        assert_eq!(
+            Tableau::from_compact_str(&bad_col),
+            Err(ParseError::FaceDownAboveFaceUp { column: 0 })
+        );
+    }
+
+    #[test]
+    fn face_up_run_length_counts_run_from_exposed_card() {
+        let column = |face_down: &[&str], face_up: &[&str]| {
+            let mut t = Tableau::new_empty();
+            for s in face_down {
+                t.columns[0].push(Card::from_short_str(s).unwrap(), true);
+            }
+            for s in face_up {
+                t.columns[0].push(Card::from_short_str(s).unwrap(), false);
+            }
+            t
+        };
+
+        assert_eq!(column(&[], &[]).face_up_run_length(0), 0);
+        assert_eq!(column(&[], &["KH", "QS", "JD", "TC"]).face_up_run_length(0), 4);
+        assert_eq!(column(&[], &["KH", "QS", "JD", "JC"]).face_up_run_length(0), 1);
+        assert_eq!(column(&[], &["9C", "KH", "QS", "JD"]).face_up_run_length(0), 3);
+        // Face-down cards never count, even if they would extend the run.
+        assert_eq!(column(&["KH", "QS"], &["JD", "TC"]).face_up_run_length(0), 2);
+    }
+
+    #[test]
+    fn valid_run_from_bottom_is_the_exposed_run() {
+        let mut full: Column<MAX_COL> = Column::new();
+        full.push(Card::from_short_str("5C").unwrap(), true);
+        for (i, &rank) in Rank::ALL.iter().rev().enumerate() {
+            let suit = if i % 2 == 0 { Suit::Spades } else { Suit::Hearts };
+            full.push(Card::new(suit, rank), false);
+        }
+        let run = full.valid_run_from_bottom();
+        assert_eq!(run.len(), 13);
+        assert_eq!(run[0].rank(), Rank::King);
+        assert_eq!(run.last().copied(), full.top());
+
+        let mut broken: Column<MAX_COL> = Column::new();
+        for s in ["KH", "QS", "JD", "JC"] {
+            broken.push(Card::from_short_str(s).unwrap(), false);
+        }
+        assert_eq!(broken.valid_run_from_bottom(), &[Card::from_short_str("JC").unwrap()]);
+
+        let mut hidden: Column<MAX_COL> = Column::new();
+        hidden.push(Card::from_short_str("KH").unwrap(), true);
+        assert!(hidden.valid_run_from_bottom().is_empty());
+        assert!(Column::<MAX_COL>::new().valid_run_from_bottom().is_empty());
+    }
+
+    #[test]
+    fn fresh_deal_hides_21_column_cards() {
+        let t = Tableau::deal_from_shuffled(standard_deck());
+        assert_eq!(t.num_hidden_cards(), 21);
+        assert_eq!(t.num_face_up_tableau_cards(), 7);
+        assert_eq!(t.stock.len(), 24);
+        assert_eq!(t.waste.len(), 0);
+        assert_eq!(t.foundations, [0; NUM_FOUNDATIONS]);
+        assert!(!t.is_auto_completable());
+
+        assert_eq!(t.pile_heights(), [1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(t.column_face_down_counts(), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!((t.min_pile_height(), t.max_pile_height()), (1, 7));
+
+        assert_eq!(Tableau::new_empty().num_hidden_cards(), 0);
+        assert!(Tableau::new_empty().is_auto_completable());
+    }
+
+    #[test]
+    fn export_pysol_indices_inverts_deal() {
+        for seed in [1u32, 7, 42] {
+            let deck = crate::card::shuffled_deck_from_seed(seed);
+            let t = Tableau::deal_from_shuffled(deck);
+            let expected: Vec<u8> = deck.iter().map(|c| c.index()).collect();
+            assert_eq!(t.export_pysol_indices().to_vec(), expected, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn pile_iter_rev_and_peek_n() {
+        let mut pile: Pile<24> = Pile::new();
+        assert_eq!(pile.iter_rev().count(), 0);
+        assert!(pile.peek_n(3).is_empty());
+
+        for i in 0..5 {
+            pile.push(Card(i));
+        }
+        let top_first: Vec<u8> = pile.iter_rev().map(|c| c.0).collect();
+        assert_eq!(top_first, vec![4, 3, 2, 1, 0]);
+
+        assert_eq!(pile.peek_n(3), &[Card(2), Card(3), Card(4)]);
+        assert_eq!(pile.peek_n(3).last().copied(), pile.top());
+        assert_eq!(pile.peek_n(0), &[]);
+        assert_eq!(pile.peek_n(10).len(), 5);
+    }
+
+    #[test]
+    fn column_permutations_share_a_canonical_form() {
+        let tab = Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(3));
+        let mut permuted = tab;
+        permuted.columns.reverse();
+        permuted.columns.swap(0, 4);
+
+        assert_ne!(tab.compute_hash(), permuted.compute_hash());
+        let canon = tab.flatten_to_canonical();
+        assert_eq!(canon, permuted.flatten_to_canonical());
+        assert_eq!(canon.compute_hash(), permuted.flatten_to_canonical().compute_hash());
+        assert_eq!(canon.flatten_to_canonical(), canon, "canonical form is a fixed point");
+
+        // Columns move as whole piles: same set of (face-down count, cards).
+        let piles = |t: &Tableau| {
+            let mut v: Vec<(u8, Vec<u8>)> = t
+                .columns
+                .iter()
+                .map(|c| (c.num_face_down, c.iter_all().map(|card| card.index()).collect()))
+                .collect();
+            v.sort();
+            v
+        };
+        assert_eq!(piles(&canon), piles(&tab));
+        assert_eq!(canon.pile_heights(), [1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn fresh_deal_exposes_one_top_card_per_column() {
+        let tab = Tableau::deal_from_shuffled(standard_deck());
+        let tops = tab.accessible_top_cards();
+        assert_eq!(tops.len(), 7, "waste is empty after the deal");
+        for (card, col) in tops.iter().zip(tab.columns.iter()) {
+            assert_eq!(Some(*card), col.top());
+        }
+        // standard_deck deals AS onto column 2, so an Ace is already playable.
+        assert!(tops.contains(&Card::new(Suit::Spades, Rank::Ace)));
+        assert!(tab.can_reach_foundation_immediately());
+
+        let mut tab = Tableau::new_empty();
+        tab.columns[0].push(Card::new(Suit::Hearts, Rank::Two), false);
+        tab.columns[1].push(Card::new(Suit::Clubs, Rank::Ace), true);
+        tab.waste.push(Card::new(Suit::Spades, Rank::Five));
+        assert_eq!(
+            tab.accessible_top_cards(),
+            vec![Card::new(Suit::Hearts, Rank::Two), Card::new(Suit::Spades, Rank::Five)]
+        );
+        assert!(!tab.can_reach_foundation_immediately(), "the Ace is face-down");
+    }
+
+    /// `column_top_face_up_card` is `Some` exactly when the column has a
+    /// face-up card, and then it is the column's top card.
+    #[test]
+    fn column_top_face_up_card_matches_face_up_count() {
+        use crate::moves::generate_legal_moves;
+
+        let check = |tab: &Tableau| {
+            for col_idx in 0..NUM_COLS {
+                let col = &tab.columns[col_idx];
+                let top = tab.column_top_face_up_card(col_idx);
+                assert_eq!(top.is_some(), col.num_face_up() > 0);
+                if top.is_some() {
+                    assert_eq!(top, col.top());
+                }
+            }
+        };
+
+        for seed in 0..50 {
+            let mut tab = Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(seed));
+            // Fully face-down columns never report a top card.
+            let mut hidden = tab;
+            for col in hidden.columns.iter_mut() {
+                col.num_face_down = col.len;
+            }
+            check(&hidden);
+
+            let mut rng: u32 = seed;
+            for _ in 0..200 {
+                check(&tab);
+                let moves = generate_legal_moves(&tab);
+                if moves.is_empty() {
+                    break;
+                }
+                rng = rng.wrapping_mul(1664525).wrapping_add(1013904223);
+                moves[(rng >> 8) as usize % moves.len()].apply(&mut tab);
+            }
+        }
+    }
+
+    #[test]
+    fn is_column_invariant_valid_detects_violations() {
+        let tab = Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(3));
+        assert!(tab.is_column_invariant_valid());
+
+        // Top card of a non-empty column turned face-down.
+        let mut hidden_top = tab;
+        hidden_top.columns[2].num_face_down = hidden_top.columns[2].len;
+        assert!(!hidden_top.is_column_invariant_valid());
+
+        // More face-down cards than cards.
+        let mut overcount = tab;
+        overcount.columns[0].num_face_down = 2;
+        assert!(!overcount.is_column_invariant_valid());
+
+        // A card lost from the stock.
+        let mut short = tab;
+        short.stock.pop();
+        assert!(!short.is_column_invariant_valid());
+
+        // Foundation rank past King.
+        let mut over_king = tab;
+        over_king.foundations[1] = 14;
+        assert!(!over_king.is_column_invariant_valid());
+    }
+
+    #[test]
+    fn stock_accessible_count_from_fresh_deal() {
+        let deck = crate::card::shuffled_deck_from_seed(4);
+
+        let tab = Tableau::deal_from_shuffled_with_rule(deck, DrawRule::Draw3);
+        assert_eq!(tab.stock.len(), 24);
+        assert_eq!(tab.stock_accessible_count(), 8);
+        // Every third card from the top of the stock, in dealing order.
+        let stock_top_down: Vec<Card> = tab.stock.iter_rev().copied().collect();
+        let expected: Vec<Card> = stock_top_down.iter().skip(2).step_by(3).copied().collect();
+        assert_eq!(reachable_waste_cards(&tab), expected);
+
+        let tab = Tableau::deal_from_shuffled_with_rule(deck, DrawRule::Draw1);
+        assert_eq!(tab.stock_accessible_count(), 24);
+        assert_eq!(reachable_waste_cards(&tab), stock_top_down);
+
+        // Mid-pass under draw-1: waste cards come back after the redeal.
+        let mut mid = tab;
+        for _ in 0..5 {
+            Move::deal_from_stock(&mid).apply(&mut mid);
+        }
+        assert_eq!(mid.stock_accessible_count(), mid.stock.len() + mid.waste.len());
+        assert_eq!(reachable_waste_cards(&mid)[0], mid.waste.top().unwrap());
+    }
+
+    #[test]
+    fn column_truncate_to_and_pop_top_face_up() {
+        let c = Card::new;
+        let mut col: Column<MAX_COL> = Column::new();
+        col.push(c(Suit::Spades, Rank::Nine), true);
+        col.push(c(Suit::Hearts, Rank::Four), true);
+        col.push(c(Suit::Clubs, Rank::King), false);
+        col.push(c(Suit::Diamonds, Rank::Queen), false);
+        col.push(c(Suit::Spades, Rank::Jack), false);
+
+        // Trimming face-up cards keeps the face-down count.
+        let mut t = col;
+        t.truncate_to(4);
+        assert_eq!((t.len(), t.num_face_down()), (4, 2));
+        assert_eq!(t.top(), Some(c(Suit::Diamonds, Rank::Queen)));
+
+        // Cutting off every face-up card leaves a face-down top.
+        let mut t = col;
+        t.truncate_to(1);
+        assert_eq!((t.len(), t.num_face_down()), (1, 1));
+        assert_eq!(t.pop_top_face_up(), None);
+        t.truncate_to(0);
+        assert!(t.is_empty() && t.num_face_down() == 0);
+
+        // Popping the face-up cards flips the last face-down card.
+        let mut t = col;
+        assert_eq!(t.pop_top_face_up(), Some(c(Suit::Spades, Rank::Jack)));
+        assert_eq!(t.pop_top_face_up(), Some(c(Suit::Diamonds, Rank::Queen)));
+        assert_eq!((t.len(), t.num_face_down()), (3, 2));
+        assert_eq!(t.pop_top_face_up(), Some(c(Suit::Clubs, Rank::King)));
+        assert_eq!((t.len(), t.num_face_down()), (2, 1));
+        assert_eq!(t.pop_top_face_up(), Some(c(Suit::Hearts, Rank::Four)));
+        assert_eq!(t.pop_top_face_up(), Some(c(Suit::Spades, Rank::Nine)));
+        assert_eq!(t.pop_top_face_up(), None);
+        assert!(t.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "truncate_to")]
+    fn column_truncate_to_cannot_grow() {
+        let mut col: Column<MAX_COL> = Column::new();
+        col.truncate_to(1);
+    }
+
+    #[test]
+    fn fresh_deal_has_seven_face_up_tops() {
+        let tab = Tableau::deal_from_shuffled(crate::card::standard_deck());
+        let tops: Vec<(usize, Card)> = tab.iter_face_up_tops().collect();
+        assert_eq!(tops.len(), 7);
+        for &(col_idx, card) in &tops {
+            let col = &tab.columns[col_idx];
+            assert!(col.num_face_up() > 0);
+            assert_eq!(col.iter_face_up().last(), Some(&card));
+            assert_eq!(tab.column_top_face_up_card(col_idx), Some(card));
+        }
+        assert_eq!(tops.iter().map(|&(c, _)| c).collect::<Vec<_>>(), (0..7).collect::<Vec<_>>());
+
+        // Empty and all-face-down columns are skipped.
+        let mut tab = tab;
+        tab.columns[0].truncate_to(0);
+        tab.columns[3].truncate_to(3);
+        let cols: Vec<usize> = tab.iter_face_up_tops().map(|(c, _)| c).collect();
+        assert_eq!(cols, [1, 2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn sort_columns_canonically_equates_column_permutations() {
+        let mut a = Tableau::deal_from_shuffled(crate::card::shuffled_deck_from_seed(11));
+        // An all-face-down column and an empty one (with stale cards).
+        a.columns[4].truncate_to(3);
+        a.columns[6].truncate_to(0);
+        let mut b = a;
+        b.swap_columns(0, 6);
+        b.swap_columns(2, 5);
+        b.swap_columns(3, 4);
+        b.swap_columns(1, 3);
+        assert!(a != b);
+
+        a.sort_columns_canonically();
+        b.sort_columns_canonically();
+        assert!(a == b);
+        assert!(a.columns[0].is_empty());
+        let keys: Vec<(u8, u8)> = a.columns.iter().map(|c| (c.num_face_down(), c.len())).collect();
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]), "{:?}", keys);
+
+        // Sorting is idempotent, and is the canonical form.
+        let once = a;
+        a.sort_columns_canonically();
+        assert!(a == once);
+        assert!(b.flatten_to_canonical() == a);
+    }
 }